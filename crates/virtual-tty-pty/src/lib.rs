@@ -137,9 +137,13 @@ impl PtyAdapter {
                 match n {
                     0 => break, // EOF
                     n if n > 0 => {
-                        let data = String::from_utf8_lossy(&read_buffer[..n as usize]);
+                        // The terminal keeps characters split across reads
                         let mut tty = virtual_tty.lock().unwrap();
-                        write!(tty.stdout, "{}", data).unwrap();
+                        tty.stdout.write_all(&read_buffer[..n as usize]).unwrap();
+                        // Answer the queries of the child like a real terminal
+                        for response in tty.take_responses() {
+                            let _ = write_to_fd(master_fd, response.as_bytes());
+                        }
                     }
                     -1 => {
                         let errno = unsafe { *libc::__error() };
@@ -194,18 +198,7 @@ impl PtyAdapter {
             .master_fd
             .ok_or_else(|| io::Error::other("No master PTY"))?;
 
-        let result = unsafe {
-            libc::write(
-                master_fd,
-                input.as_ptr() as *const libc::c_void,
-                input.len(),
-            )
-        };
-        if result < 0 {
-            Err(io::Error::last_os_error())
-        } else {
-            Ok(())
-        }
+        write_to_fd(master_fd, input)
     }
 
     /// Convenience method to send string input
@@ -230,6 +223,15 @@ impl PtyAdapter {
     }
 }
 
+fn write_to_fd(fd: RawFd, data: &[u8]) -> io::Result<()> {
+    let result = unsafe { libc::write(fd, data.as_ptr() as *const libc::c_void, data.len()) };
+    if result < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
 impl Drop for PtyAdapter {
     fn drop(&mut self) {
         // Signal shutdown to reader thread
@@ -30,6 +30,11 @@ impl VirtualTtyWasm {
     pub fn get_snapshot(&self) -> String {
         self.streams.get_snapshot()
     }
+
+    #[wasm_bindgen(js_name = getStyledSnapshot)]
+    pub fn get_styled_snapshot(&self) -> String {
+        self.streams.get_styled_snapshot()
+    }
 }
 
 #[wasm_bindgen(js_name = createVirtualTTY)]
@@ -1,13 +1,210 @@
+use crate::cell::{Color, Hyperlink};
+use crate::charset::Charset;
+use crate::clipboard::{decode_base64, DEFAULT_SELECTION};
+use crate::notification::Notification;
+use crate::progress::{Progress, ProgressState};
+use crate::semantic::SemanticMark;
+use crate::title::TitleTarget;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum AnsiCommand {
     CursorUp(usize),
     CursorDown(usize),
     CursorForward(usize),
     CursorBack(usize),
-    CursorPosition { row: usize, col: usize },
+    CursorPosition {
+        row: usize,
+        col: usize,
+    },
+    /// CHA (`CSI G`) / HPA (`` CSI ` ``): move to a zero-based column on the current line
+    CursorColumn(usize),
+    /// VPA (`CSI d`): move to a zero-based row keeping the column
+    CursorRow(usize),
+    /// CNL (`CSI E`): move down and to the first column
+    CursorNextLine(usize),
+    /// CPL (`CSI F`): move up and to the first column
+    CursorPreviousLine(usize),
     ClearScreen(ClearMode),
     ClearLine(ClearMode),
-    SetGraphicsRendition,
+    /// ICH: insert blank characters at the cursor, shifting the rest of the line right
+    InsertChars(usize),
+    /// DCH: delete characters at the cursor, shifting the rest of the line left
+    DeleteChars(usize),
+    /// ECH: blank characters from the cursor without shifting
+    EraseChars(usize),
+    /// IL: insert blank lines at the cursor row within the scroll region
+    InsertLines(usize),
+    /// DL: delete lines at the cursor row within the scroll region
+    DeleteLines(usize),
+    /// SU: scroll the content of the scroll region up
+    ScrollUp(usize),
+    /// SD: scroll the content of the scroll region down
+    ScrollDown(usize),
+    /// IND (`ESC D`): move down one line, scrolling at the bottom margin
+    Index,
+    /// RI (`ESC M`): move up one line, scrolling at the top margin
+    ReverseIndex,
+    /// NEL (`ESC E`): move to the first column of the next line, scrolling at the bottom margin
+    NextLine,
+    /// `CSI 3 J`: erase the scrollback, leaving the screen untouched
+    ClearScrollback,
+    SetGraphicsRendition(Vec<GraphicsRendition>),
+    /// DECSTBM: zero-based top and bottom lines (inclusive) of the scroll
+    /// region, `None` for the bottom meaning the last line of the screen
+    SetScrollRegion {
+        top: usize,
+        bottom: Option<usize>,
+    },
+    SetMode(Vec<Mode>),
+    ResetMode(Vec<Mode>),
+    /// DECSLRM (`CSI Pl ; Pr s`): zero-based left and right columns (inclusive)
+    /// of the horizontal margins, `None` for the right meaning the last column.
+    /// While DECLRMM is reset the sequence is SCOSC and saves the cursor instead.
+    SetLeftRightMargins {
+        left: usize,
+        right: Option<usize>,
+    },
+    /// DECSC (`ESC 7`)
+    SaveCursor,
+    /// DECRC (`ESC 8`) or SCORC (`CSI u`)
+    RestoreCursor,
+    /// HTS (`ESC H`): set a tab stop at the cursor column
+    SetTabStop,
+    /// TBC (`CSI g` / `CSI 3 g`): clear the tab stop at the cursor column or all of them
+    ClearTabStop(TabClearMode),
+    /// CHT (`CSI I`): move forward to the nth next tab stop
+    ForwardTab(usize),
+    /// CBT (`CSI Z`): move back to the nth previous tab stop
+    BackTab(usize),
+    /// RIS (`ESC c`): reset the terminal to its initial state
+    Reset,
+    /// `ESC ( X` / `ESC ) X`: select the character set used by G0 or G1
+    DesignateCharset {
+        slot: CharsetSlot,
+        charset: Charset,
+    },
+    /// OSC 0/1/2: set the window title, the icon name or both
+    SetTitle {
+        target: TitleTarget,
+        title: String,
+    },
+    /// OSC 8: start a hyperlink for the following text, or end it with `None`
+    SetHyperlink(Option<Hyperlink>),
+    /// OSC 7: the shell reports its working directory, decoded from a
+    /// `file://host/path` URL
+    SetWorkingDirectory(String),
+    /// OSC 9 (`OSC 9 ; body`) or OSC 777 (`OSC 777 ; notify ; title ; body`)
+    Notify(Notification),
+    /// `OSC 9 ; 4 ; state ; percent`: set the progress indicator
+    SetProgress(Progress),
+    /// OSC 52 with data: copy text to the selection, clearing it when the
+    /// text is empty or the payload is not valid base64
+    SetClipboard {
+        selection: String,
+        content: String,
+    },
+    /// OSC 52 with `?` as data: ask for the content of the selection
+    QueryClipboard {
+        selection: String,
+    },
+    /// OSC 133: shell integration mark around prompts and commands
+    SemanticPrompt(SemanticMark),
+    /// DSR `CSI 5 n`: ask whether the terminal is working
+    StatusReport,
+    /// CPR `CSI 6 n`: ask for the cursor position
+    CursorPositionReport,
+    /// DA1 `CSI c`: ask for the terminal's conformance level and features
+    PrimaryDeviceAttributes,
+    /// DA2 `CSI > c`: ask for the terminal type and version
+    SecondaryDeviceAttributes,
+    /// XTVERSION `CSI > q`: ask for the terminal's name and version
+    TerminalVersion,
+    /// XTWINOPS `CSI 22 ; Ps t`: save titles on the title stack
+    PushTitle(TitleTarget),
+    /// XTWINOPS `CSI 23 ; Ps t`: restore titles from the title stack
+    PopTitle(TitleTarget),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CharsetSlot {
+    G0,
+    G1,
+}
+
+/// Terminal modes toggled by SM/RM (`CSI Pm h` / `CSI Pm l`) and their DEC
+/// private counterparts (`CSI ? Pm h` / `CSI ? Pm l`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    /// `4` (IRM): printed characters are inserted instead of replacing
+    Insert,
+    /// `20` (LNM): line feeds also move the cursor to the first column
+    NewLine,
+    /// `?6` (DECOM): cursor addressing is relative to the margins
+    Origin,
+    /// `?69` (DECLRMM): enable left and right margins set by DECSLRM
+    LeftRightMargins,
+    /// `?7` (DECAWM): wrap to the next line when printing past the last column
+    AutoWrap,
+    /// `?47`: switch between the primary and alternate screen
+    AlternateScreen,
+    /// `?1047`: like `?47`, but the alternate screen is cleared when leaving it
+    AlternateScreenClear,
+    /// `?1049`: save the cursor and switch to a cleared alternate screen,
+    /// restoring the cursor when switching back
+    AlternateScreenSaveCursor,
+}
+
+impl Mode {
+    pub fn from_ansi(param: usize) -> Option<Self> {
+        match param {
+            4 => Some(Mode::Insert),
+            20 => Some(Mode::NewLine),
+            _ => None,
+        }
+    }
+
+    pub fn from_private(param: usize) -> Option<Self> {
+        match param {
+            6 => Some(Mode::Origin),
+            7 => Some(Mode::AutoWrap),
+            69 => Some(Mode::LeftRightMargins),
+            47 => Some(Mode::AlternateScreen),
+            1047 => Some(Mode::AlternateScreenClear),
+            1049 => Some(Mode::AlternateScreenSaveCursor),
+            _ => None,
+        }
+    }
+}
+
+/// A single attribute change requested by an SGR (`CSI ... m`) sequence
+#[derive(Debug, Clone, PartialEq)]
+pub enum GraphicsRendition {
+    Reset,
+    Bold,
+    Dim,
+    Italic,
+    Underline,
+    Blink,
+    Reverse,
+    Hidden,
+    Strikethrough,
+    NormalIntensity,
+    NoItalic,
+    NoUnderline,
+    NoBlink,
+    NoReverse,
+    NoHidden,
+    NoStrikethrough,
+    Foreground(Color),
+    Background(Color),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TabClearMode {
+    /// Clear the tab stop at the cursor column
+    Current,
+    /// Clear all tab stops
+    All,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -34,9 +231,14 @@ pub enum ControlChar {
     Bell,
     VerticalTab,
     FormFeed,
+    /// SO: invoke the G1 character set
+    ShiftOut,
+    /// SI: invoke the G0 character set
+    ShiftIn,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[allow(dead_code)]
 pub enum ParseError {
     InvalidEscapeSequence(String),
     InvalidParameter(String),
@@ -106,9 +308,23 @@ impl AnsiCommand {
                 let col = params.get(1).copied().unwrap_or(1).saturating_sub(1);
                 Ok(AnsiCommand::CursorPosition { row, col })
             }
+            'G' | '`' => {
+                let col = params.first().copied().unwrap_or(1).saturating_sub(1);
+                Ok(AnsiCommand::CursorColumn(col))
+            }
+            'd' => {
+                let row = params.first().copied().unwrap_or(1).saturating_sub(1);
+                Ok(AnsiCommand::CursorRow(row))
+            }
+            'E' => Ok(AnsiCommand::CursorNextLine(Self::count(params))),
+            'F' => Ok(AnsiCommand::CursorPreviousLine(Self::count(params))),
+            // HPR and VPR behave like CUF and CUD
+            'a' => Ok(AnsiCommand::CursorForward(Self::count(params))),
+            'e' => Ok(AnsiCommand::CursorDown(Self::count(params))),
             'J' => {
                 let param = params.first().copied().unwrap_or(0);
                 let clear_mode = match param {
+                    3 => return Ok(AnsiCommand::ClearScrollback),
                     2 => ClearMode::Entire,
                     1 => ClearMode::ToBeginning,
                     0 => ClearMode::ToEnd,
@@ -116,7 +332,7 @@ impl AnsiCommand {
                         return Err(ParseError::InvalidParameterRange {
                             param: "clear_screen".to_string(),
                             min: 0,
-                            max: 2,
+                            max: 3,
                         })
                     }
                 };
@@ -138,37 +354,465 @@ impl AnsiCommand {
                 };
                 Ok(AnsiCommand::ClearLine(clear_mode))
             }
-            'm' => Ok(AnsiCommand::SetGraphicsRendition),
+            '@' => Ok(AnsiCommand::InsertChars(Self::count(params))),
+            'P' => Ok(AnsiCommand::DeleteChars(Self::count(params))),
+            'X' => Ok(AnsiCommand::EraseChars(Self::count(params))),
+            'L' => Ok(AnsiCommand::InsertLines(Self::count(params))),
+            'M' => Ok(AnsiCommand::DeleteLines(Self::count(params))),
+            'S' => Ok(AnsiCommand::ScrollUp(Self::count(params))),
+            // With more parameters `CSI T` is xterm's mouse highlight tracking
+            'T' if params.len() <= 1 => Ok(AnsiCommand::ScrollDown(Self::count(params))),
+            'g' => {
+                let param = params.first().copied().unwrap_or(0);
+                let clear_mode = match param {
+                    0 => TabClearMode::Current,
+                    3 => TabClearMode::All,
+                    _ => {
+                        return Err(ParseError::InvalidParameterRange {
+                            param: "clear_tab_stop".to_string(),
+                            min: 0,
+                            max: 3,
+                        })
+                    }
+                };
+                Ok(AnsiCommand::ClearTabStop(clear_mode))
+            }
+            'I' => Ok(AnsiCommand::ForwardTab(Self::count(params))),
+            'Z' => Ok(AnsiCommand::BackTab(Self::count(params))),
+            'r' => {
+                let top = params.first().copied().unwrap_or(1).saturating_sub(1);
+                let bottom = params
+                    .get(1)
+                    .copied()
+                    .filter(|&bottom| bottom > 0)
+                    .map(|bottom| bottom - 1);
+                Ok(AnsiCommand::SetScrollRegion { top, bottom })
+            }
+            // Unsupported modes are ignored rather than rejecting the whole sequence
+            'h' => Ok(AnsiCommand::SetMode(
+                params.iter().filter_map(|&p| Mode::from_ansi(p)).collect(),
+            )),
+            'l' => Ok(AnsiCommand::ResetMode(
+                params.iter().filter_map(|&p| Mode::from_ansi(p)).collect(),
+            )),
+            's' => {
+                let left = params.first().copied().unwrap_or(1).saturating_sub(1);
+                let right = params
+                    .get(1)
+                    .copied()
+                    .filter(|&right| right > 0)
+                    .map(|right| right - 1);
+                Ok(AnsiCommand::SetLeftRightMargins { left, right })
+            }
+            't' if matches!(params.first(), Some(22 | 23)) => {
+                let target = match params.get(1).copied().unwrap_or(0) {
+                    0 => TitleTarget::IconAndWindow,
+                    1 => TitleTarget::IconName,
+                    2 => TitleTarget::Window,
+                    _ => {
+                        return Err(ParseError::InvalidParameterRange {
+                            param: "title_stack".to_string(),
+                            min: 0,
+                            max: 2,
+                        })
+                    }
+                };
+                if params[0] == 22 {
+                    Ok(AnsiCommand::PushTitle(target))
+                } else {
+                    Ok(AnsiCommand::PopTitle(target))
+                }
+            }
+            'u' if params.is_empty() => Ok(AnsiCommand::RestoreCursor),
+            'n' => match params.first() {
+                Some(5) => Ok(AnsiCommand::StatusReport),
+                Some(6) => Ok(AnsiCommand::CursorPositionReport),
+                _ => Err(ParseError::InvalidParameter(format!(
+                    "Unknown device status report: {params:?}"
+                ))),
+            },
+            'c' if params.iter().all(|&param| param == 0) => {
+                Ok(AnsiCommand::PrimaryDeviceAttributes)
+            }
+            'm' => {
+                let groups: Vec<Vec<usize>> = params.iter().map(|&param| vec![param]).collect();
+                Self::from_sgr_parameters(&groups)
+            }
             _ => Err(ParseError::InvalidEscapeSequence(format!(
                 "Unknown CSI command: {cmd}"
             ))),
         }
     }
 
+    /// Returns the repeat count given as first parameter, defaulting to 1
+    fn count(params: &[usize]) -> usize {
+        let n = params.first().copied().unwrap_or(1);
+        if n == 0 {
+            1 // Treat 0 as 1
+        } else {
+            n
+        }
+    }
+
+    /// Builds a command from an escape sequence that is not a CSI sequence,
+    /// given its intermediate characters and final character
+    pub fn from_esc_command(intermediates: &str, cmd: char) -> Result<Self, ParseError> {
+        match (intermediates, cmd) {
+            ("", '7') => Ok(AnsiCommand::SaveCursor),
+            ("", 'D') => Ok(AnsiCommand::Index),
+            ("", 'M') => Ok(AnsiCommand::ReverseIndex),
+            ("", 'E') => Ok(AnsiCommand::NextLine),
+            ("", 'H') => Ok(AnsiCommand::SetTabStop),
+            ("", 'c') => Ok(AnsiCommand::Reset),
+            ("", '8') => Ok(AnsiCommand::RestoreCursor),
+            ("(" | ")", designator) => {
+                let slot = if intermediates == "(" {
+                    CharsetSlot::G0
+                } else {
+                    CharsetSlot::G1
+                };
+                let charset = Charset::from_designator(designator).ok_or_else(|| {
+                    ParseError::InvalidParameter(format!("Unknown charset: {designator}"))
+                })?;
+                Ok(AnsiCommand::DesignateCharset { slot, charset })
+            }
+            _ => Err(ParseError::InvalidEscapeSequence(format!(
+                "Unknown escape sequence: {intermediates}{cmd}"
+            ))),
+        }
+    }
+
+    /// Builds a command from a CSI sequence whose parameters start with `>`
+    pub fn from_secondary_csi_command(cmd: char, params: &[usize]) -> Result<Self, ParseError> {
+        let default_params = params.iter().all(|&param| param == 0);
+        match cmd {
+            'c' if default_params => Ok(AnsiCommand::SecondaryDeviceAttributes),
+            'q' if default_params => Ok(AnsiCommand::TerminalVersion),
+            _ => Err(ParseError::InvalidEscapeSequence(format!(
+                "Unknown CSI command: >{cmd}"
+            ))),
+        }
+    }
+
+    /// Builds a command from the payload of an OSC sequence, the text between
+    /// `ESC ]` and the string terminator
+    pub fn from_osc_command(data: &str) -> Result<Self, ParseError> {
+        let (command, text) = data.split_once(';').unwrap_or((data, ""));
+        let title = |target| {
+            Ok(AnsiCommand::SetTitle {
+                target,
+                title: text.to_string(),
+            })
+        };
+        match command {
+            "0" => title(TitleTarget::IconAndWindow),
+            "1" => title(TitleTarget::IconName),
+            "2" => title(TitleTarget::Window),
+            "8" => {
+                let (params, uri) = text.split_once(';').ok_or_else(|| {
+                    ParseError::InvalidParameter(format!("Missing hyperlink URI: {text}"))
+                })?;
+                if uri.is_empty() {
+                    return Ok(AnsiCommand::SetHyperlink(None));
+                }
+                // Parameters are `key=value` pairs separated by colons
+                let id = params
+                    .split(':')
+                    .find_map(|param| param.strip_prefix("id="))
+                    .filter(|id| !id.is_empty())
+                    .map(str::to_string);
+                Ok(AnsiCommand::SetHyperlink(Some(Hyperlink {
+                    uri: uri.to_string(),
+                    id,
+                })))
+            }
+            "7" => {
+                let location = text.strip_prefix("file://").ok_or_else(|| {
+                    ParseError::InvalidParameter(format!("Unsupported working directory: {text}"))
+                })?;
+                // The path starts after the host name, which may be empty
+                let path = location.find('/').map_or("/", |start| &location[start..]);
+                Ok(AnsiCommand::SetWorkingDirectory(Self::percent_decode(path)))
+            }
+            "9" if text == "4" || text.starts_with("4;") => {
+                let mut params = text.split(';').skip(1);
+                let state = params.next().filter(|param| !param.is_empty());
+                let state = state
+                    .map_or(Some(ProgressState::Cleared), |param| {
+                        param.parse().ok().and_then(ProgressState::from_param)
+                    })
+                    .ok_or_else(|| {
+                        ParseError::InvalidParameter(format!("Unknown progress state: {text}"))
+                    })?;
+                let percent = match state {
+                    ProgressState::Cleared | ProgressState::Indeterminate => None,
+                    _ => params
+                        .next()
+                        .and_then(|param| param.parse::<usize>().ok())
+                        .map(|percent| percent.min(100) as u8),
+                };
+                Ok(AnsiCommand::SetProgress(Progress { state, percent }))
+            }
+            "9" => Ok(AnsiCommand::Notify(Notification {
+                title: String::new(),
+                body: text.to_string(),
+            })),
+            "777" => {
+                let mut params = text.splitn(3, ';');
+                if params.next() != Some("notify") {
+                    return Err(ParseError::InvalidParameter(format!(
+                        "Unknown OSC 777 command: {text}"
+                    )));
+                }
+                Ok(AnsiCommand::Notify(Notification {
+                    title: params.next().unwrap_or_default().to_string(),
+                    body: params.next().unwrap_or_default().to_string(),
+                }))
+            }
+            "52" => {
+                let (selection, data) = text.split_once(';').ok_or_else(|| {
+                    ParseError::InvalidParameter(format!("Missing clipboard data: {text}"))
+                })?;
+                let selection = if selection.is_empty() {
+                    DEFAULT_SELECTION.to_string()
+                } else {
+                    selection.to_string()
+                };
+                if data == "?" {
+                    return Ok(AnsiCommand::QueryClipboard { selection });
+                }
+                let content = decode_base64(data)
+                    .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+                    .unwrap_or_default();
+                Ok(AnsiCommand::SetClipboard { selection, content })
+            }
+            "133" => {
+                let mut params = text.split(';');
+                let mark = match params.next() {
+                    Some("A") => SemanticMark::PromptStart,
+                    Some("B") => SemanticMark::CommandStart,
+                    Some("C") => SemanticMark::OutputStart,
+                    // Options like `aid=...` may follow the exit status
+                    Some("D") => SemanticMark::CommandFinished(
+                        params.next().and_then(|code| code.parse().ok()),
+                    ),
+                    _ => {
+                        return Err(ParseError::InvalidParameter(format!(
+                            "Unknown semantic prompt mark: {text}"
+                        )))
+                    }
+                };
+                Ok(AnsiCommand::SemanticPrompt(mark))
+            }
+            _ => Err(ParseError::InvalidEscapeSequence(format!(
+                "Unknown OSC command: {command}"
+            ))),
+        }
+    }
+
+    /// Decodes the `%XX` escapes of a URL component. Malformed escapes are kept
+    /// as they are.
+    fn percent_decode(input: &str) -> String {
+        let bytes = input.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut index = 0;
+        while index < bytes.len() {
+            let escaped = (bytes[index] == b'%')
+                .then(|| input.get(index + 1..index + 3))
+                .flatten()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            match escaped {
+                Some(byte) => {
+                    decoded.push(byte);
+                    index += 3;
+                }
+                None => {
+                    decoded.push(bytes[index]);
+                    index += 1;
+                }
+            }
+        }
+        String::from_utf8_lossy(&decoded).into_owned()
+    }
+
+    /// Builds a command from a CSI sequence whose parameters start with the
+    /// private marker `?`
+    pub fn from_private_csi_command(cmd: char, params: &[usize]) -> Result<Self, ParseError> {
+        // Unsupported modes are ignored rather than rejecting the whole sequence
+        let modes = || {
+            params
+                .iter()
+                .filter_map(|&p| Mode::from_private(p))
+                .collect()
+        };
+        match cmd {
+            'h' => Ok(AnsiCommand::SetMode(modes())),
+            'l' => Ok(AnsiCommand::ResetMode(modes())),
+            _ => Err(ParseError::InvalidEscapeSequence(format!(
+                "Unknown private CSI command: ?{cmd}"
+            ))),
+        }
+    }
+
+    /// Builds an SGR command from parameter groups, where each group holds a
+    /// parameter followed by its colon-separated subparameters (if any)
+    pub fn from_sgr_parameters(groups: &[Vec<usize>]) -> Result<Self, ParseError> {
+        // An empty parameter list is equivalent to a single 0 (reset)
+        if groups.is_empty() {
+            return Ok(AnsiCommand::SetGraphicsRendition(vec![
+                GraphicsRendition::Reset,
+            ]));
+        }
+
+        let mut renditions = Vec::new();
+        let mut index = 0;
+        while index < groups.len() {
+            let group = &groups[index];
+            index += 1;
+
+            let param = group.first().copied().unwrap_or(0);
+            let rendition = match param {
+                0 => Some(GraphicsRendition::Reset),
+                1 => Some(GraphicsRendition::Bold),
+                2 => Some(GraphicsRendition::Dim),
+                3 => Some(GraphicsRendition::Italic),
+                // `4:0` explicitly disables underlining, other styles (curly, dotted...) enable it
+                4 if group.get(1) == Some(&0) => Some(GraphicsRendition::NoUnderline),
+                4 => Some(GraphicsRendition::Underline),
+                5 | 6 => Some(GraphicsRendition::Blink),
+                7 => Some(GraphicsRendition::Reverse),
+                8 => Some(GraphicsRendition::Hidden),
+                9 => Some(GraphicsRendition::Strikethrough),
+                21 => Some(GraphicsRendition::Underline), // Doubly underlined
+                22 => Some(GraphicsRendition::NormalIntensity),
+                23 => Some(GraphicsRendition::NoItalic),
+                24 => Some(GraphicsRendition::NoUnderline),
+                25 => Some(GraphicsRendition::NoBlink),
+                27 => Some(GraphicsRendition::NoReverse),
+                28 => Some(GraphicsRendition::NoHidden),
+                29 => Some(GraphicsRendition::NoStrikethrough),
+                30..=37 => Some(GraphicsRendition::Foreground(Color::Indexed(
+                    (param - 30) as u8,
+                ))),
+                38 => Some(GraphicsRendition::Foreground(Self::parse_extended_color(
+                    group, groups, &mut index,
+                )?)),
+                39 => Some(GraphicsRendition::Foreground(Color::Default)),
+                40..=47 => Some(GraphicsRendition::Background(Color::Indexed(
+                    (param - 40) as u8,
+                ))),
+                48 => Some(GraphicsRendition::Background(Self::parse_extended_color(
+                    group, groups, &mut index,
+                )?)),
+                49 => Some(GraphicsRendition::Background(Color::Default)),
+                58 => {
+                    // Underline color is not tracked, but its arguments must still be skipped
+                    Self::parse_extended_color(group, groups, &mut index)?;
+                    None
+                }
+                90..=97 => Some(GraphicsRendition::Foreground(Color::Indexed(
+                    (param - 90 + 8) as u8,
+                ))),
+                100..=107 => Some(GraphicsRendition::Background(Color::Indexed(
+                    (param - 100 + 8) as u8,
+                ))),
+                // Unsupported attributes are ignored, like a real terminal does
+                _ => None,
+            };
+            renditions.extend(rendition);
+        }
+
+        Ok(AnsiCommand::SetGraphicsRendition(renditions))
+    }
+
+    /// Parses the color selected by SGR 38/48/58, either from the group's own
+    /// colon subparameters (`38:2::r:g:b`, `38:5:n`) or from the following
+    /// semicolon-separated parameters (`38;2;r;g;b`, `38;5;n`)
+    fn parse_extended_color(
+        group: &[usize],
+        groups: &[Vec<usize>],
+        index: &mut usize,
+    ) -> Result<Color, ParseError> {
+        let args: Vec<usize> = if group.len() > 1 {
+            let mut args = group[1..].to_vec();
+            // The ITU form carries a color space identifier before the components,
+            // which xterm's `38:2:r:g:b` shorthand omits
+            if args.first() == Some(&2) && args.len() >= 5 {
+                args.remove(1);
+            }
+            args
+        } else {
+            let mode = groups.get(*index).and_then(|g| g.first()).copied();
+            let needed = match mode {
+                Some(5) => 2,
+                Some(2) => 4,
+                Some(_) => 1,
+                None => {
+                    return Err(ParseError::InvalidParameterCount {
+                        expected: 2,
+                        actual: 1,
+                    })
+                }
+            };
+            let available = groups.len() - *index;
+            if available < needed {
+                return Err(ParseError::InvalidParameterCount {
+                    expected: needed + 1,
+                    actual: available + 1,
+                });
+            }
+            let args = groups[*index..*index + needed]
+                .iter()
+                .map(|g| g.first().copied().unwrap_or(0))
+                .collect();
+            *index += needed;
+            args
+        };
+
+        let component = |value: usize| -> Result<u8, ParseError> {
+            u8::try_from(value).map_err(|_| ParseError::InvalidParameterRange {
+                param: "color".to_string(),
+                min: 0,
+                max: 255,
+            })
+        };
+
+        match args.as_slice() {
+            [5, n, ..] => Ok(Color::Indexed(component(*n)?)),
+            [2, r, g, b, ..] => Ok(Color::Rgb(component(*r)?, component(*g)?, component(*b)?)),
+            [5] | [2, ..] => Err(ParseError::InvalidParameterCount {
+                expected: if args[0] == 5 { 2 } else { 4 },
+                actual: args.len(),
+            }),
+            _ => Err(ParseError::InvalidParameter(format!(
+                "Unsupported color mode: {:?}",
+                args.first()
+            ))),
+        }
+    }
+
     pub fn validate(&self) -> Result<(), ParseError> {
         match self {
             AnsiCommand::CursorUp(n)
             | AnsiCommand::CursorDown(n)
             | AnsiCommand::CursorForward(n)
-            | AnsiCommand::CursorBack(n) => {
+            | AnsiCommand::CursorBack(n)
                 // Movement parameters should be valid (0 is already converted to 1 in parsing)
-                if *n == 0 {
-                    return Err(ParseError::InvalidParameterRange {
-                        param: "cursor_movement".to_string(),
-                        min: 1,
-                        max: usize::MAX,
-                    });
-                }
+                if *n == 0 =>
+            {
+                return Err(ParseError::InvalidParameterRange {
+                    param: "cursor_movement".to_string(),
+                    min: 1,
+                    max: usize::MAX,
+                });
             }
-            AnsiCommand::CursorPosition { row, col } => {
-                // Reasonable bounds for cursor position
-                if *row > 65535 || *col > 65535 {
-                    return Err(ParseError::InvalidParameterRange {
-                        param: "cursor_position".to_string(),
-                        min: 0,
-                        max: 65535,
-                    });
-                }
+            // Reasonable bounds for cursor position
+            AnsiCommand::CursorPosition { row, col } if *row > 65535 || *col > 65535 => {
+                return Err(ParseError::InvalidParameterRange {
+                    param: "cursor_position".to_string(),
+                    min: 0,
+                    max: 65535,
+                });
             }
             _ => {
                 // All other commands are valid by default
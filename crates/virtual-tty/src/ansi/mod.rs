@@ -1,5 +1,7 @@
 pub mod commands;
 pub mod parser;
 
-pub use commands::{AnsiCommand, ClearMode, ControlChar, Token};
-pub use parser::{parse_escape_sequence, AnsiParser};
+pub use commands::{
+    AnsiCommand, CharsetSlot, ClearMode, ControlChar, GraphicsRendition, Mode, TabClearMode, Token,
+};
+pub use parser::{parse_escape_sequence, StreamingParser};
@@ -32,6 +32,17 @@ impl<'a> AnsiParser<'a> {
                         Ok(command) => Ok(Some(Token::Command(command))),
                         Err(e) => Ok(Some(Token::Invalid(format!("CSI parse error: {e:?}")))),
                     }
+                } else if self.chars.peek() == Some(&']') {
+                    self.chars.next(); // consume ']'
+                    match self.parse_osc_sequence() {
+                        Ok(command) => Ok(Some(Token::Command(command))),
+                        Err(e) => Ok(Some(Token::Invalid(format!("OSC parse error: {e:?}")))),
+                    }
+                } else if self.chars.peek().is_some() {
+                    match self.parse_esc_sequence() {
+                        Ok(command) => Ok(Some(Token::Command(command))),
+                        Err(e) => Ok(Some(Token::Invalid(format!("ESC parse error: {e:?}")))),
+                    }
                 } else {
                     Ok(Some(Token::Invalid(
                         "Incomplete escape sequence".to_string(),
@@ -45,6 +56,8 @@ impl<'a> AnsiParser<'a> {
             Some('\x07') => Ok(Some(Token::ControlChar(ControlChar::Bell))),
             Some('\x0b') => Ok(Some(Token::ControlChar(ControlChar::VerticalTab))),
             Some('\x0c') => Ok(Some(Token::ControlChar(ControlChar::FormFeed))),
+            Some('\x0e') => Ok(Some(Token::ControlChar(ControlChar::ShiftOut))),
+            Some('\x0f') => Ok(Some(Token::ControlChar(ControlChar::ShiftIn))),
             Some(ch) => {
                 let mut text = String::new();
                 text.push(ch);
@@ -59,6 +72,8 @@ impl<'a> AnsiParser<'a> {
                         || next_ch == '\x07'
                         || next_ch == '\x0b'
                         || next_ch == '\x0c'
+                        || next_ch == '\x0e'
+                        || next_ch == '\x0f'
                     {
                         break;
                     }
@@ -70,13 +85,58 @@ impl<'a> AnsiParser<'a> {
         }
     }
 
+    /// Parses an escape sequence other than CSI: optional intermediate bytes
+    /// (0x20-0x2F) followed by a final character
+    fn parse_esc_sequence(&mut self) -> Result<AnsiCommand, ParseError> {
+        let mut intermediates = String::new();
+        while let Some(&ch) = self.chars.peek() {
+            if !(' '..='/').contains(&ch) {
+                break;
+            }
+            intermediates.push(self.chars.next().unwrap());
+        }
+
+        let cmd = self.chars.next().ok_or(ParseError::UnexpectedEndOfInput)?;
+        AnsiCommand::from_esc_command(&intermediates, cmd)
+    }
+
+    /// Parses an operating system command up to its terminator: BEL, ST
+    /// (`ESC \`) or the 8-bit ST. Another escape sequence cancels the OSC.
+    fn parse_osc_sequence(&mut self) -> Result<AnsiCommand, ParseError> {
+        let mut data = String::new();
+        loop {
+            match self.chars.peek() {
+                None => return Err(ParseError::UnexpectedEndOfInput),
+                Some('\x07' | '\u{9c}') => {
+                    self.chars.next();
+                    break;
+                }
+                Some('\x1b') => {
+                    let mut ahead = self.chars.clone();
+                    ahead.next();
+                    if ahead.peek() != Some(&'\\') {
+                        // Leave the escape sequence to be parsed on its own
+                        return Err(ParseError::InvalidEscapeSequence(format!(
+                            "Unterminated OSC: {data}"
+                        )));
+                    }
+                    self.chars.next();
+                    self.chars.next();
+                    break;
+                }
+                Some(_) => data.push(self.chars.next().unwrap()),
+            }
+        }
+        AnsiCommand::from_osc_command(&data)
+    }
+
     fn parse_csi_sequence(&mut self) -> Result<AnsiCommand, ParseError> {
         let mut param_str = String::new();
         let mut command_char = None;
 
-        // Read parameters and find command character
+        // Read parameters and find the final byte (0x40-0x7E) ending the sequence
         while let Some(&ch) = self.chars.peek() {
-            if ch.is_ascii_alphabetic() || ch == '~' {
+            if ('@'..='~').contains(&ch) {
                 command_char = Some(self.chars.next().unwrap());
                 break;
             }
@@ -84,6 +144,19 @@ impl<'a> AnsiParser<'a> {
         }
 
         let cmd = command_char.ok_or(ParseError::UnexpectedEndOfInput)?;
+        if let Some(private_params) = param_str.strip_prefix('?') {
+            let params = self.parse_parameters(private_params)?;
+            return AnsiCommand::from_private_csi_command(cmd, &params);
+        }
+        if let Some(secondary_params) = param_str.strip_prefix('>') {
+            let params = self.parse_parameters(secondary_params)?;
+            return AnsiCommand::from_secondary_csi_command(cmd, &params);
+        }
+        if cmd == 'm' {
+            // SGR is the only sequence that accepts colon-separated subparameters
+            let groups = self.parse_subparameters(&param_str)?;
+            return AnsiCommand::from_sgr_parameters(&groups);
+        }
         let params = self.parse_parameters(&param_str)?;
 
         AnsiCommand::from_csi_command(cmd, &params)
@@ -108,6 +181,155 @@ impl<'a> AnsiParser<'a> {
 
         Ok(params)
     }
+
+    /// Splits parameters on `;` and each parameter into its `:`-separated
+    /// subparameters, treating empty entries as 0
+    fn parse_subparameters(&self, param_str: &str) -> Result<Vec<Vec<usize>>, ParseError> {
+        if param_str.is_empty() {
+            return Ok(vec![]);
+        }
+
+        param_str
+            .split(';')
+            .map(|part| {
+                part.split(':')
+                    .map(|sub| {
+                        if sub.is_empty() {
+                            Ok(0)
+                        } else {
+                            sub.parse::<usize>().map_err(|_| {
+                                ParseError::InvalidParameter(format!(
+                                    "Cannot parse parameter: {sub}"
+                                ))
+                            })
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Longest unfinished escape sequence kept between writes. Anything longer is
+/// not a real sequence and is parsed as it is instead of being buffered forever.
+const MAX_PENDING_SEQUENCE: usize = 4096;
+
+/// Longest unfinished OSC 52 kept between writes, large enough for clipboard
+/// payloads
+const MAX_PENDING_CLIPBOARD: usize = 1 << 20;
+
+/// Parser for a stream of output arriving in arbitrary chunks. An escape
+/// sequence left unfinished at the end of a chunk is kept and completed by
+/// the next one.
+#[derive(Debug, Default)]
+pub struct StreamingParser {
+    pending: String,
+}
+
+impl StreamingParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses `input` after the unfinished sequence of the previous call
+    pub fn feed(&mut self, input: &str) -> Result<Vec<Token>, ParseError> {
+        let mut data = std::mem::take(&mut self.pending);
+        // The previous call searched the unfinished sequence up to its last
+        // byte, which may start the terminator of an OSC
+        let scanned = data.len().saturating_sub(1);
+        data.push_str(input);
+        if let Some(start) = Self::unfinished_sequence_start(&data, scanned) {
+            if data.len() - start <= Self::pending_limit(&data[start..]) {
+                self.pending = data.split_off(start);
+            }
+        }
+        AnsiParser::parse(&data)
+    }
+
+    fn pending_limit(sequence: &str) -> usize {
+        if sequence.starts_with("\x1b]52;") {
+            MAX_PENDING_CLIPBOARD
+        } else {
+            MAX_PENDING_SEQUENCE
+        }
+    }
+
+    /// Returns the position of the escape sequence at the end of `data` if it
+    /// still lacks its final character or, for an OSC, its terminator. An OSC
+    /// at the start of `data` is known not to end before byte `scanned`, so
+    /// that a long payload arriving in many writes is only searched once.
+    fn unfinished_sequence_start(data: &str, scanned: usize) -> Option<usize> {
+        let bytes = data.as_bytes();
+        let mut index = 0;
+        while index < bytes.len() {
+            if bytes[index] != 0x1b {
+                index += 1;
+                continue;
+            }
+            let start = index;
+            let rest = &bytes[start + 1..];
+            let length = match rest.first() {
+                None => return Some(start),
+                Some(b'[') => rest[1..]
+                    .iter()
+                    .position(|byte| (b'@'..=b'~').contains(byte))
+                    .map(|length| length + 1),
+                Some(b']') => {
+                    // Indices in `rest` are one less than in `data`
+                    let from = if start == 0 {
+                        scanned.saturating_sub(1)
+                    } else {
+                        0
+                    };
+                    match Self::osc_length(rest, from) {
+                        OscEnd::Terminated(length) => Some(length),
+                        // The escape sequence cancelling the OSC is checked next
+                        OscEnd::Cancelled(length) => Some(length - 1),
+                        OscEnd::Unfinished => None,
+                    }
+                }
+                // Intermediate bytes are followed by the final character
+                Some(b' '..=b'/') => rest.iter().position(|byte| !(b' '..=b'/').contains(byte)),
+                Some(_) => Some(0),
+            };
+            match length {
+                Some(length) => index = start + 2 + length,
+                None => return Some(start),
+            }
+        }
+        None
+    }
+
+    /// Finds the end of the OSC starting with `]` at the beginning of `data`,
+    /// searching from byte `from`
+    fn osc_length(data: &[u8], from: usize) -> OscEnd {
+        let mut index = from.max(1);
+        while index < data.len() {
+            match data[index] {
+                0x07 => return OscEnd::Terminated(index),
+                // 8-bit ST, encoded in UTF-8
+                0xc2 if data.get(index + 1) == Some(&0x9c) => return OscEnd::Terminated(index + 1),
+                0x1b => {
+                    return match data.get(index + 1) {
+                        None => OscEnd::Unfinished,
+                        Some(b'\\') => OscEnd::Terminated(index + 1),
+                        Some(_) => OscEnd::Cancelled(index),
+                    }
+                }
+                _ => index += 1,
+            }
+        }
+        OscEnd::Unfinished
+    }
+}
+
+/// How an OSC found by [`StreamingParser::osc_length`] ends, with the index
+/// of its last byte
+enum OscEnd {
+    Terminated(usize),
+    /// Ended by the escape sequence at the given index
+    Cancelled(usize),
+    Unfinished,
 }
 
 // Legacy function for backward compatibility
@@ -142,7 +364,14 @@ pub fn parse_escape_sequence(chars: &mut std::str::Chars) -> Option<AnsiCommand>
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ansi::{AnsiCommand, ClearMode};
+    use crate::ansi::CharsetSlot;
+    use crate::ansi::{AnsiCommand, ClearMode, GraphicsRendition, Mode, TabClearMode};
+    use crate::cell::{Color, Hyperlink};
+    use crate::charset::Charset;
+    use crate::notification::Notification;
+    use crate::progress::{Progress, ProgressState};
+    use crate::semantic::SemanticMark;
+    use crate::title::TitleTarget;
 
     #[test]
     fn test_parse_cursor_up() {
@@ -187,6 +416,161 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_graphics_rendition() {
+        let tokens = AnsiParser::parse("\x1b[1;31;42m").unwrap();
+        assert_eq!(
+            tokens,
+            vec![Token::Command(AnsiCommand::SetGraphicsRendition(vec![
+                GraphicsRendition::Bold,
+                GraphicsRendition::Foreground(Color::RED),
+                GraphicsRendition::Background(Color::GREEN),
+            ]))]
+        );
+    }
+
+    #[test]
+    fn test_parse_graphics_rendition_no_param() {
+        let tokens = AnsiParser::parse("\x1b[m").unwrap();
+        assert_eq!(
+            tokens,
+            vec![Token::Command(AnsiCommand::SetGraphicsRendition(vec![
+                GraphicsRendition::Reset
+            ]))]
+        );
+    }
+
+    #[test]
+    fn test_parse_graphics_rendition_subparameters() {
+        let tokens = AnsiParser::parse("\x1b[38:2::1:2:3;48:5:200m").unwrap();
+        assert_eq!(
+            tokens,
+            vec![Token::Command(AnsiCommand::SetGraphicsRendition(vec![
+                GraphicsRendition::Foreground(Color::Rgb(1, 2, 3)),
+                GraphicsRendition::Background(Color::Indexed(200)),
+            ]))]
+        );
+    }
+
+    #[test]
+    fn test_parse_graphics_rendition_color_out_of_range() {
+        let tokens = AnsiParser::parse("\x1b[38;5;256m").unwrap();
+        assert!(matches!(tokens[0], Token::Invalid(_)));
+    }
+
+    #[test]
+    fn test_parse_private_modes() {
+        let tokens = AnsiParser::parse("\x1b[?1049h\x1b[?47;1047l\x1b[?9999h").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Command(AnsiCommand::SetMode(vec![Mode::AlternateScreenSaveCursor])),
+                Token::Command(AnsiCommand::ResetMode(vec![
+                    Mode::AlternateScreen,
+                    Mode::AlternateScreenClear
+                ])),
+                Token::Command(AnsiCommand::SetMode(vec![])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_ansi_modes() {
+        let tokens = AnsiParser::parse("\x1b[4h\x1b[20;4l\x1b[?4h").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Command(AnsiCommand::SetMode(vec![Mode::Insert])),
+                Token::Command(AnsiCommand::ResetMode(vec![Mode::NewLine, Mode::Insert])),
+                // DEC private mode 4 (smooth scroll) is not IRM
+                Token::Command(AnsiCommand::SetMode(vec![])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_left_right_margins() {
+        let tokens = AnsiParser::parse("\x1b[3;6s\x1b[s\x1b[?6;69h").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Command(AnsiCommand::SetLeftRightMargins {
+                    left: 2,
+                    right: Some(5)
+                }),
+                Token::Command(AnsiCommand::SetLeftRightMargins {
+                    left: 0,
+                    right: None
+                }),
+                Token::Command(AnsiCommand::SetMode(vec![
+                    Mode::Origin,
+                    Mode::LeftRightMargins
+                ])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_line_and_column_positioning() {
+        let tokens = AnsiParser::parse("\x1b[5G\x1b[`\x1b[3d\x1b[2E\x1b[0F\x1b[4a\x1b[e").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Command(AnsiCommand::CursorColumn(4)),
+                Token::Command(AnsiCommand::CursorColumn(0)),
+                Token::Command(AnsiCommand::CursorRow(2)),
+                Token::Command(AnsiCommand::CursorNextLine(2)),
+                Token::Command(AnsiCommand::CursorPreviousLine(1)),
+                Token::Command(AnsiCommand::CursorForward(4)),
+                Token::Command(AnsiCommand::CursorDown(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_tab_stop_commands() {
+        let tokens = AnsiParser::parse("\x1bH\x1b[g\x1b[3g\x1b[2I\x1b[Z\x1bc").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Command(AnsiCommand::SetTabStop),
+                Token::Command(AnsiCommand::ClearTabStop(TabClearMode::Current)),
+                Token::Command(AnsiCommand::ClearTabStop(TabClearMode::All)),
+                Token::Command(AnsiCommand::ForwardTab(2)),
+                Token::Command(AnsiCommand::BackTab(1)),
+                Token::Command(AnsiCommand::Reset),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_esc_sequences() {
+        let tokens = AnsiParser::parse("\x1b7\x1b8\x1b(0\x1b)B").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Command(AnsiCommand::SaveCursor),
+                Token::Command(AnsiCommand::RestoreCursor),
+                Token::Command(AnsiCommand::DesignateCharset {
+                    slot: CharsetSlot::G0,
+                    charset: Charset::DecSpecialGraphics,
+                }),
+                Token::Command(AnsiCommand::DesignateCharset {
+                    slot: CharsetSlot::G1,
+                    charset: Charset::Ascii,
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_unknown_esc_sequence_consumes_final() {
+        let tokens = AnsiParser::parse("\x1bZx").unwrap();
+        assert_eq!(tokens.len(), 2);
+        assert!(matches!(tokens[0], Token::Invalid(_)));
+        assert_eq!(tokens[1], Token::Text("x".to_string()));
+    }
+
     #[test]
     fn test_parse_mixed_content() {
         let tokens = AnsiParser::parse("Hello\x1b[2JWorld").unwrap();
@@ -236,7 +620,7 @@ mod tests {
 
     #[test]
     fn test_parse_invalid_sequence() {
-        let tokens = AnsiParser::parse("\x1b[999Z").unwrap();
+        let tokens = AnsiParser::parse("\x1b[999y").unwrap();
         assert_eq!(tokens.len(), 1);
         match &tokens[0] {
             Token::Invalid(_) => {}
@@ -244,6 +628,262 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_streaming_parser_resumes_split_sequences() {
+        let mut parser = StreamingParser::new();
+        assert_eq!(
+            parser.feed("a\x1b[3").unwrap(),
+            vec![Token::Text("a".to_string())]
+        );
+        assert_eq!(parser.feed("1").unwrap(), vec![]);
+        assert_eq!(
+            parser.feed("mb\x1b").unwrap(),
+            vec![
+                Token::Command(AnsiCommand::SetGraphicsRendition(vec![
+                    GraphicsRendition::Foreground(Color::RED)
+                ])),
+                Token::Text("b".to_string()),
+            ]
+        );
+        assert_eq!(parser.feed("(").unwrap(), vec![]);
+        assert_eq!(
+            parser.feed("0").unwrap(),
+            vec![Token::Command(AnsiCommand::DesignateCharset {
+                slot: CharsetSlot::G0,
+                charset: Charset::DecSpecialGraphics,
+            })]
+        );
+    }
+
+    #[test]
+    fn test_parse_osc_terminators() {
+        let tokens =
+            AnsiParser::parse("\x1b]0;one\x07\x1b]2;two\x1b\\\x1b]1;three\u{9c}x").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Command(AnsiCommand::SetTitle {
+                    target: TitleTarget::IconAndWindow,
+                    title: "one".to_string()
+                }),
+                Token::Command(AnsiCommand::SetTitle {
+                    target: TitleTarget::Window,
+                    title: "two".to_string()
+                }),
+                Token::Command(AnsiCommand::SetTitle {
+                    target: TitleTarget::IconName,
+                    title: "three".to_string()
+                }),
+                Token::Text("x".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_osc_cancelled_by_escape_sequence() {
+        let tokens = AnsiParser::parse("\x1b]0;title\x1b[1mx").unwrap();
+        assert!(matches!(tokens[0], Token::Invalid(_)));
+        assert_eq!(
+            tokens[1..],
+            [
+                Token::Command(AnsiCommand::SetGraphicsRendition(vec![
+                    GraphicsRendition::Bold
+                ])),
+                Token::Text("x".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_hyperlinks() {
+        let tokens =
+            AnsiParser::parse("\x1b]8;id=42:x=y;https://a.io/?q=1;2\x07\x1b]8;;\x07\x1b]8\x07")
+                .unwrap();
+        assert_eq!(
+            tokens[..2],
+            [
+                Token::Command(AnsiCommand::SetHyperlink(Some(Hyperlink {
+                    uri: "https://a.io/?q=1;2".to_string(),
+                    id: Some("42".to_string()),
+                }))),
+                Token::Command(AnsiCommand::SetHyperlink(None)),
+            ]
+        );
+        assert!(matches!(tokens[2], Token::Invalid(_)));
+    }
+
+    #[test]
+    fn test_parse_clipboard() {
+        let tokens =
+            AnsiParser::parse("\x1b]52;c;aGVsbG8=\x07\x1b]52;;?\x07\x1b]52;p;!!\x07").unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Command(AnsiCommand::SetClipboard {
+                    selection: "c".to_string(),
+                    content: "hello".to_string(),
+                }),
+                Token::Command(AnsiCommand::QueryClipboard {
+                    selection: "s0".to_string(),
+                }),
+                // Data that is not base64 clears the selection
+                Token::Command(AnsiCommand::SetClipboard {
+                    selection: "p".to_string(),
+                    content: String::new(),
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_semantic_prompt_marks() {
+        let tokens = AnsiParser::parse(
+            "\x1b]133;A\x07\x1b]133;B\x07\x1b]133;C\x07\x1b]133;D;1;aid=7\x07\x1b]133;D\x07",
+        )
+        .unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Command(AnsiCommand::SemanticPrompt(SemanticMark::PromptStart)),
+                Token::Command(AnsiCommand::SemanticPrompt(SemanticMark::CommandStart)),
+                Token::Command(AnsiCommand::SemanticPrompt(SemanticMark::OutputStart)),
+                Token::Command(AnsiCommand::SemanticPrompt(SemanticMark::CommandFinished(
+                    Some(1)
+                ))),
+                Token::Command(AnsiCommand::SemanticPrompt(SemanticMark::CommandFinished(
+                    None
+                ))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_working_directory_and_notifications() {
+        let tokens = AnsiParser::parse(
+            "\x1b]7;file://host/home/me/my%20dir%C3%A9\x07\x1b]7;file:///\x07\x1b]9;Done\x07\x1b]777;notify;Build;ok; 0 errors\x07",
+        )
+        .unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Command(AnsiCommand::SetWorkingDirectory(
+                    "/home/me/my dir\u{e9}".to_string()
+                )),
+                Token::Command(AnsiCommand::SetWorkingDirectory("/".to_string())),
+                Token::Command(AnsiCommand::Notify(Notification {
+                    title: String::new(),
+                    body: "Done".to_string(),
+                })),
+                Token::Command(AnsiCommand::Notify(Notification {
+                    title: "Build".to_string(),
+                    body: "ok; 0 errors".to_string(),
+                })),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_progress() {
+        let tokens = AnsiParser::parse(
+            "\x1b]9;4;1;42\x07\x1b]9;4;2\x07\x1b]9;4;3;50\x07\x1b]9;4;4;250\x07\x1b]9;4\x07\x1b]9;4;7\x07",
+        )
+        .unwrap();
+        let progress =
+            |state, percent| Token::Command(AnsiCommand::SetProgress(Progress { state, percent }));
+        assert_eq!(
+            tokens[..5],
+            [
+                progress(ProgressState::Normal, Some(42)),
+                progress(ProgressState::Error, None),
+                progress(ProgressState::Indeterminate, None),
+                progress(ProgressState::Paused, Some(100)),
+                progress(ProgressState::Cleared, None),
+            ]
+        );
+        assert!(matches!(tokens[5], Token::Invalid(_)));
+    }
+
+    #[test]
+    fn test_parse_terminal_queries() {
+        let tokens =
+            AnsiParser::parse("\x1b[5n\x1b[6n\x1b[c\x1b[0c\x1b[>c\x1b[>0q\x1b[>4;1m\x1b[3n")
+                .unwrap();
+        assert_eq!(
+            tokens[..6],
+            [
+                Token::Command(AnsiCommand::StatusReport),
+                Token::Command(AnsiCommand::CursorPositionReport),
+                Token::Command(AnsiCommand::PrimaryDeviceAttributes),
+                Token::Command(AnsiCommand::PrimaryDeviceAttributes),
+                Token::Command(AnsiCommand::SecondaryDeviceAttributes),
+                Token::Command(AnsiCommand::TerminalVersion),
+            ]
+        );
+        // xterm's modifyOtherKeys is not SGR
+        assert!(matches!(tokens[6], Token::Invalid(_)));
+        assert!(matches!(tokens[7], Token::Invalid(_)));
+    }
+
+    #[test]
+    fn test_parse_title_stack() {
+        let tokens = AnsiParser::parse("\x1b[22t\x1b[22;2t\x1b[23;1t\x1b[8;24;80t").unwrap();
+        assert_eq!(
+            tokens[..3],
+            [
+                Token::Command(AnsiCommand::PushTitle(TitleTarget::IconAndWindow)),
+                Token::Command(AnsiCommand::PushTitle(TitleTarget::Window)),
+                Token::Command(AnsiCommand::PopTitle(TitleTarget::IconName)),
+            ]
+        );
+        // Other window operations are not supported
+        assert!(matches!(tokens[3], Token::Invalid(_)));
+    }
+
+    #[test]
+    fn test_streaming_parser_resumes_split_osc() {
+        let mut parser = StreamingParser::new();
+        assert_eq!(parser.feed("\x1b]0;ti").unwrap(), vec![]);
+        assert_eq!(parser.feed("tle\x1b").unwrap(), vec![]);
+        assert_eq!(
+            parser.feed("\\").unwrap(),
+            vec![Token::Command(AnsiCommand::SetTitle {
+                target: TitleTarget::IconAndWindow,
+                title: "title".to_string()
+            })]
+        );
+        // An OSC containing `[` or a final byte is not mistaken for a CSI
+        assert_eq!(parser.feed("\x1b]2;[x]").unwrap(), vec![]);
+        assert_eq!(parser.feed("\x07").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_streaming_parser_limits_unfinished_osc() {
+        let mut parser = StreamingParser::new();
+        // An unterminated title gives up once it is too long to be real
+        assert_eq!(parser.feed("\x1b]0;").unwrap(), vec![]);
+        let tokens = parser.feed(&"x".repeat(MAX_PENDING_SEQUENCE)).unwrap();
+        assert!(matches!(tokens[..], [Token::Invalid(_)]));
+        assert_eq!(
+            parser.feed("ok").unwrap(),
+            vec![Token::Text("ok".to_string())]
+        );
+
+        // A clipboard payload may be much longer, and arrive in many pieces
+        assert_eq!(parser.feed("\x1b]52;c;").unwrap(), vec![]);
+        for _ in 0..100 {
+            assert_eq!(parser.feed(&"QUFB".repeat(100)).unwrap(), vec![]);
+        }
+        assert_eq!(parser.feed("\x1b").unwrap(), vec![]);
+        let tokens = parser.feed("\\").unwrap();
+        assert_eq!(
+            tokens,
+            vec![Token::Command(AnsiCommand::SetClipboard {
+                selection: "c".to_string(),
+                content: "A".repeat(30000),
+            })]
+        );
+    }
+
     #[test]
     fn test_legacy_parser_compatibility() {
         let mut chars = "1A".chars();
@@ -1,57 +1,277 @@
+use std::collections::VecDeque;
+
+use crate::cell::{Cell, CellAttributes, HyperlinkSpan};
+
 pub struct Buffer {
-    pub lines: Vec<Vec<char>>,
+    pub lines: Vec<Vec<Cell>>,
     pub width: usize,
     pub height: usize,
+    /// Lines scrolled off the top of the screen, oldest first
+    pub scrollback: VecDeque<Vec<Cell>>,
+    /// Maximum number of lines kept in `scrollback`, 0 disables it
+    pub scrollback_limit: usize,
+    /// Number of lines that ever scrolled off the top of the screen,
+    /// including those dropped from the scrollback. Rows counted from the
+    /// first line ever displayed ("history rows") are offset by it.
+    pub scrolled_off: usize,
 }
 
 impl Buffer {
     pub fn new(width: usize, height: usize) -> Self {
-        let lines = vec![vec![' '; width]; height];
+        Self::with_scrollback(width, height, 0)
+    }
+
+    pub fn with_scrollback(width: usize, height: usize, scrollback_limit: usize) -> Self {
+        let lines = vec![vec![Cell::default(); width]; height];
         Self {
             lines,
             width,
             height,
+            scrollback: VecDeque::new(),
+            scrollback_limit,
+            scrolled_off: 0,
         }
     }
 
     pub fn resize_from(old_buffer: &Buffer, new_width: usize, new_height: usize) -> Self {
-        let mut new_lines = vec![vec![' '; new_width]; new_height];
+        let mut new_lines = vec![vec![Cell::default(); new_width]; new_height];
 
         // Copy existing content within the bounds of the new buffer
         let copy_height = old_buffer.height.min(new_height);
         let copy_width = old_buffer.width.min(new_width);
 
-        for row in 0..copy_height {
-            for col in 0..copy_width {
-                new_lines[row][col] = old_buffer.lines[row][col];
-            }
+        for (new_line, old_line) in new_lines
+            .iter_mut()
+            .zip(&old_buffer.lines)
+            .take(copy_height)
+        {
+            new_line[..copy_width].clone_from_slice(&old_line[..copy_width]);
         }
 
         Self {
             lines: new_lines,
             width: new_width,
             height: new_height,
+            scrollback: old_buffer.scrollback.clone(),
+            scrollback_limit: old_buffer.scrollback_limit,
+            scrolled_off: old_buffer.scrolled_off,
         }
     }
 
     pub fn clear(&mut self) {
-        self.lines = vec![vec![' '; self.width]; self.height];
+        self.lines = vec![vec![Cell::default(); self.width]; self.height];
+    }
+
+    /// Scrolls the rectangle between lines `top` and `bottom` and columns
+    /// `left` and `right` (all inclusive) up by `n`, filling its bottom with
+    /// blank cells. Full lines leaving the top of the screen are moved to the
+    /// scrollback.
+    pub fn scroll_up(&mut self, top: usize, bottom: usize, left: usize, right: usize, n: usize) {
+        if self.is_empty() {
+            return;
+        }
+        if top == 0 && self.is_full_width(left, right) {
+            for row in 0..n.min(bottom + 1) {
+                let line = self.lines[row].clone();
+                self.push_scrollback(line);
+            }
+        }
+        self.delete_lines(top, bottom, left, right, n);
+    }
+
+    /// Scrolls the rectangle between lines `top` and `bottom` and columns
+    /// `left` and `right` (all inclusive) down by `n`, filling its top with
+    /// blank cells
+    pub fn scroll_down(&mut self, top: usize, bottom: usize, left: usize, right: usize, n: usize) {
+        self.insert_lines(top, bottom, left, right, n);
+    }
+
+    /// Removes `n` lines starting at `row`, pulling up the lines below it
+    /// until `bottom` (inclusive) and blanking the freed lines. Only the
+    /// columns between `left` and `right` (inclusive) are affected.
+    pub fn delete_lines(&mut self, row: usize, bottom: usize, left: usize, right: usize, n: usize) {
+        if self.is_empty() {
+            return;
+        }
+        let n = n.min(bottom + 1 - row);
+        if self.is_full_width(left, right) {
+            self.lines[row..=bottom].rotate_left(n);
+        } else {
+            for r in row..bottom + 1 - n {
+                let moved = self.lines[r + n][left..=right].to_vec();
+                self.lines[r][left..=right].clone_from_slice(&moved);
+            }
+        }
+        for line in &mut self.lines[bottom + 1 - n..=bottom] {
+            line[left..=right].fill(Cell::default());
+        }
+    }
+
+    /// Inserts `n` blank lines at `row`, pushing the lines below it down and
+    /// discarding those moved past `bottom` (inclusive). Only the columns
+    /// between `left` and `right` (inclusive) are affected.
+    pub fn insert_lines(&mut self, row: usize, bottom: usize, left: usize, right: usize, n: usize) {
+        if self.is_empty() {
+            return;
+        }
+        let n = n.min(bottom + 1 - row);
+        if self.is_full_width(left, right) {
+            self.lines[row..=bottom].rotate_right(n);
+        } else {
+            for r in (row + n..=bottom).rev() {
+                let moved = self.lines[r - n][left..=right].to_vec();
+                self.lines[r][left..=right].clone_from_slice(&moved);
+            }
+        }
+        for line in &mut self.lines[row..row + n] {
+            line[left..=right].fill(Cell::default());
+        }
+    }
+
+    /// Whether the screen has no cells at all, after a resize to zero lines or
+    /// columns
+    fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    fn is_full_width(&self, left: usize, right: usize) -> bool {
+        left == 0 && right + 1 == self.width
+    }
+
+    /// Inserts `n` blank cells at the given position, shifting the cells up to
+    /// column `right` (inclusive) right and dropping those moved past it
+    pub fn insert_chars(&mut self, row: usize, col: usize, right: usize, n: usize) {
+        if row < self.height && col <= right && right < self.width {
+            self.split_wide_char(row, col);
+            self.split_wide_char(row, right + 1);
+            let line = &mut self.lines[row][col..=right];
+            let n = n.min(line.len());
+            line.rotate_right(n);
+            line[..n].fill(Cell::default());
+            // A wide character pushed onto the last column lost its second half
+            if line[line.len() - 1].width == 2 {
+                line[line.len() - 1] = Cell::default();
+            }
+        }
     }
 
-    pub fn scroll_up(&mut self) {
-        self.lines.remove(0);
-        self.lines.push(vec![' '; self.width]);
+    /// Deletes `n` cells at the given position, shifting the cells up to
+    /// column `right` (inclusive) left and blanking the cells freed before it
+    pub fn delete_chars(&mut self, row: usize, col: usize, right: usize, n: usize) {
+        if row < self.height && col <= right && right < self.width {
+            let n = n.min(right + 1 - col);
+            self.split_wide_char(row, col);
+            self.split_wide_char(row, col + n);
+            self.split_wide_char(row, right + 1);
+            let line = &mut self.lines[row][col..=right];
+            line.rotate_left(n);
+            let len = line.len();
+            line[len - n..].fill(Cell::default());
+        }
     }
 
-    pub fn set_char(&mut self, row: usize, col: usize, ch: char) {
+    /// Blanks `n` cells starting at the given position without shifting
+    pub fn erase_chars(&mut self, row: usize, col: usize, n: usize) {
         if row < self.height && col < self.width {
-            self.lines[row][col] = ch;
+            let end = col.saturating_add(n).min(self.width);
+            self.split_wide_char(row, col);
+            self.split_wide_char(row, end);
+            self.lines[row][col..end].fill(Cell::default());
         }
     }
 
-    pub fn get_char(&self, row: usize, col: usize) -> Option<char> {
+    /// Blanks both halves of a wide character that straddles the boundary
+    /// before column `col`, so that an edit starting or ending there does not
+    /// leave half of it on the screen
+    fn split_wide_char(&mut self, row: usize, col: usize) {
+        if col > 0 && col < self.width && self.lines[row][col].is_continuation() {
+            self.lines[row][col - 1] = Cell::default();
+            self.lines[row][col] = Cell::default();
+        }
+    }
+
+    fn push_scrollback(&mut self, line: Vec<Cell>) {
+        self.scrolled_off += 1;
+        if self.scrollback_limit == 0 {
+            return;
+        }
+        if self.scrollback.len() == self.scrollback_limit {
+            self.scrollback.pop_front();
+        }
+        self.scrollback.push_back(line);
+    }
+
+    pub fn set_scrollback_limit(&mut self, limit: usize) {
+        self.scrollback_limit = limit;
+        while self.scrollback.len() > limit {
+            self.scrollback.pop_front();
+        }
+    }
+
+    pub fn clear_scrollback(&mut self) {
+        self.scrollback.clear();
+    }
+
+    /// Returns the line at a history row, either in the scrollback or on the
+    /// screen, or `None` if it was dropped from the scrollback
+    pub fn history_line(&self, row: usize) -> Option<&Vec<Cell>> {
+        let first = self.scrolled_off - self.scrollback.len();
+        if row < first {
+            None
+        } else if row < self.scrolled_off {
+            self.scrollback.get(row - first)
+        } else {
+            self.lines.get(row - self.scrolled_off)
+        }
+    }
+
+    /// Returns the text from `start` (inclusive) to `end` (exclusive), given
+    /// as history row and column. Lines are separated by `\n` without their
+    /// trailing blanks, and trailing empty lines are left out.
+    pub fn history_text(&self, start: (usize, usize), end: (usize, usize)) -> String {
+        if start >= end {
+            return String::new();
+        }
+        let mut lines = Vec::new();
+        for row in start.0..=end.0 {
+            let Some(line) = self.history_line(row) else {
+                continue;
+            };
+            let from = if row == start.0 { start.1 } else { 0 };
+            let to = if row == end.0 { end.1 } else { line.len() };
+            let text: String = line[from.min(line.len())..to.min(line.len())]
+                .iter()
+                .filter(|cell| !cell.is_continuation())
+                .map(Cell::grapheme)
+                .collect();
+            lines.push(text.trim_end().to_string());
+        }
+        lines.join("\n").trim_end_matches('\n').to_string()
+    }
+
+    pub fn set_cell(&mut self, row: usize, col: usize, cell: Cell) {
         if row < self.height && col < self.width {
-            Some(self.lines[row][col])
+            let line = &mut self.lines[row];
+            // Overwriting either half of a wide character erases the other half
+            match line[col].width {
+                0 if col > 0 => line[col - 1] = Cell::default(),
+                2 if col + 1 < self.width => line[col + 1] = Cell::default(),
+                _ => {}
+            }
+            line[col] = cell;
+        }
+    }
+
+    /// Appends a zero-width character to the grapheme cluster of a cell
+    pub fn append_to_cell(&mut self, row: usize, col: usize, ch: char) {
+        if row < self.height && col < self.width {
+            self.lines[row][col].combining.push(ch);
+        }
+    }
+
+    pub fn get_cell(&self, row: usize, col: usize) -> Option<&Cell> {
+        if row < self.height && col < self.width {
+            Some(&self.lines[row][col])
         } else {
             None
         }
@@ -59,13 +279,14 @@ impl Buffer {
 
     pub fn clear_from_cursor_to_end(&mut self, cursor_row: usize, cursor_col: usize) {
         if cursor_row < self.height {
+            self.split_wide_char(cursor_row, cursor_col);
             // Clear rest of current line from cursor position
             for col in cursor_col..self.width {
-                self.lines[cursor_row][col] = ' ';
+                self.lines[cursor_row][col] = Cell::default();
             }
             // Clear all lines below current cursor row
             for row in &mut self.lines[(cursor_row + 1)..self.height] {
-                row.fill(' ');
+                row.fill(Cell::default());
             }
         }
     }
@@ -73,13 +294,14 @@ impl Buffer {
     pub fn clear_from_beginning_to_cursor(&mut self, cursor_row: usize, cursor_col: usize) {
         // Clear all complete lines above current cursor row
         for row in &mut self.lines[0..cursor_row] {
-            row.fill(' ');
+            row.fill(Cell::default());
         }
         // Clear current line from beginning to cursor position (exclusive)
         if cursor_row < self.height {
+            self.split_wide_char(cursor_row, cursor_col);
             for col in 0..cursor_col {
                 if col < self.width {
-                    self.lines[cursor_row][col] = ' ';
+                    self.lines[cursor_row][col] = Cell::default();
                 }
             }
         }
@@ -87,17 +309,19 @@ impl Buffer {
 
     pub fn clear_line_from_cursor_to_end(&mut self, cursor_row: usize, cursor_col: usize) {
         if cursor_row < self.height {
+            self.split_wide_char(cursor_row, cursor_col);
             for col in cursor_col..self.width {
-                self.lines[cursor_row][col] = ' ';
+                self.lines[cursor_row][col] = Cell::default();
             }
         }
     }
 
     pub fn clear_line_from_beginning_to_cursor(&mut self, cursor_row: usize, cursor_col: usize) {
         if cursor_row < self.height {
+            self.split_wide_char(cursor_row, cursor_col + 1);
             for col in 0..=cursor_col {
                 if col < self.width {
-                    self.lines[cursor_row][col] = ' ';
+                    self.lines[cursor_row][col] = Cell::default();
                 }
             }
         }
@@ -106,16 +330,36 @@ impl Buffer {
     pub fn clear_entire_line(&mut self, cursor_row: usize) {
         if cursor_row < self.height {
             for col in 0..self.width {
-                self.lines[cursor_row][col] = ' ';
+                self.lines[cursor_row][col] = Cell::default();
             }
         }
     }
 
     pub fn get_snapshot(&self) -> String {
+        Self::render_snapshot(&self.lines)
+    }
+
+    /// Renders the scrollback in the same layout as `get_snapshot`
+    pub fn get_scrollback_snapshot(&self) -> String {
+        Self::render_snapshot(&self.scrollback)
+    }
+
+    /// Renders the scrollback followed by the screen in the same layout as
+    /// `get_snapshot`
+    pub fn get_history_snapshot(&self) -> String {
+        Self::render_snapshot(self.scrollback.iter().chain(&self.lines))
+    }
+
+    fn render_snapshot<'a>(lines: impl IntoIterator<Item = &'a Vec<Cell>>) -> String {
         let mut result = String::new();
         result.push('\n');
-        for row in &self.lines {
-            let line: String = row.iter().collect();
+        for row in lines {
+            // Continuation cells are covered by the wide character before them
+            let line: String = row
+                .iter()
+                .filter(|cell| !cell.is_continuation())
+                .map(Cell::grapheme)
+                .collect();
             result.push_str(&line);
             // Add \\n for visual clarity in tests to show line endings, then actual \n for line break
             // Example output:
@@ -128,4 +372,93 @@ impl Buffer {
         }
         result
     }
+
+    /// Returns the runs of hyperlinked cells on the screen, from top to bottom.
+    /// A link spanning several lines gives one run per line.
+    pub fn hyperlink_spans(&self) -> Vec<HyperlinkSpan> {
+        let mut spans: Vec<HyperlinkSpan> = Vec::new();
+        for (row, line) in self.lines.iter().enumerate() {
+            let mut col = 0;
+            while col < line.len() {
+                let Some(hyperlink) = &line[col].hyperlink else {
+                    col += 1;
+                    continue;
+                };
+                let start = col;
+                while col < line.len() && line[col].hyperlink.as_ref() == Some(hyperlink) {
+                    col += 1;
+                }
+                spans.push(HyperlinkSpan {
+                    hyperlink: hyperlink.as_ref().clone(),
+                    row,
+                    columns: start..col,
+                    text: line[start..col]
+                        .iter()
+                        .filter(|cell| !cell.is_continuation())
+                        .map(Cell::grapheme)
+                        .collect(),
+                });
+            }
+        }
+        spans
+    }
+
+    /// Like `get_snapshot`, but annotates runs of styled text with their
+    /// attributes. A `[attributes]` marker is emitted wherever the attributes
+    /// change and `[/]` where they return to the defaults, so unstyled output
+    /// looks exactly like a plain snapshot. Hyperlinked text is enclosed in
+    /// `[link=URI]` and `[/link]`; a style run crossing a link boundary is
+    /// closed before the link marker and reopened after it, so markers nest.
+    //
+    // Example output:
+    // "
+    // [fg=red,bold]Error[/]: see [link=https://x.io]docs[/link]  \n
+    //           \n
+    // "
+    pub fn get_styled_snapshot(&self) -> String {
+        let mut result = String::new();
+        result.push('\n');
+        for row in &self.lines {
+            let mut current = CellAttributes::default();
+            let mut link = None;
+            for cell in row {
+                if cell.hyperlink != link {
+                    // Styles are closed around link markers so that the
+                    // markers nest, and reopened below
+                    if !current.is_default() {
+                        result.push_str("[/]");
+                        current = CellAttributes::default();
+                    }
+                    if link.is_some() {
+                        result.push_str("[/link]");
+                    }
+                    link = cell.hyperlink.clone();
+                    if let Some(hyperlink) = &link {
+                        result.push_str(&format!("[link={}]", hyperlink.uri));
+                    }
+                }
+                if cell.attributes != current {
+                    current = cell.attributes;
+                    if current.is_default() {
+                        result.push_str("[/]");
+                    } else {
+                        result.push_str(&format!("[{current}]"));
+                    }
+                }
+                if !cell.is_continuation() {
+                    result.push_str(&cell.grapheme());
+                }
+            }
+            // Close open styles and links at the end of every line to keep
+            // lines independent
+            if !current.is_default() {
+                result.push_str("[/]");
+            }
+            if link.is_some() {
+                result.push_str("[/link]");
+            }
+            result.push_str("\\n\n");
+        }
+        result
+    }
 }
@@ -0,0 +1,196 @@
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Terminal colour as selected by SGR sequences
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Color {
+    /// The terminal's default foreground or background colour
+    #[default]
+    Default,
+    /// An entry of the 256-colour palette: 0-7 are the standard ANSI colours,
+    /// 8-15 their bright variants, 16-231 a 6x6x6 cube and 232-255 a grayscale ramp
+    Indexed(u8),
+    /// A 24-bit truecolor value
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    pub const BLACK: Color = Color::Indexed(0);
+    pub const RED: Color = Color::Indexed(1);
+    pub const GREEN: Color = Color::Indexed(2);
+    pub const YELLOW: Color = Color::Indexed(3);
+    pub const BLUE: Color = Color::Indexed(4);
+    pub const MAGENTA: Color = Color::Indexed(5);
+    pub const CYAN: Color = Color::Indexed(6);
+    pub const WHITE: Color = Color::Indexed(7);
+    pub const BRIGHT_BLACK: Color = Color::Indexed(8);
+    pub const BRIGHT_RED: Color = Color::Indexed(9);
+    pub const BRIGHT_GREEN: Color = Color::Indexed(10);
+    pub const BRIGHT_YELLOW: Color = Color::Indexed(11);
+    pub const BRIGHT_BLUE: Color = Color::Indexed(12);
+    pub const BRIGHT_MAGENTA: Color = Color::Indexed(13);
+    pub const BRIGHT_CYAN: Color = Color::Indexed(14);
+    pub const BRIGHT_WHITE: Color = Color::Indexed(15);
+}
+
+impl std::fmt::Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const NAMES: [&str; 8] = [
+            "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+        ];
+        match self {
+            Color::Default => write!(f, "default"),
+            Color::Indexed(n @ 0..=7) => write!(f, "{}", NAMES[*n as usize]),
+            Color::Indexed(n @ 8..=15) => write!(f, "bright-{}", NAMES[*n as usize - 8]),
+            Color::Indexed(n) => write!(f, "{n}"),
+            Color::Rgb(r, g, b) => write!(f, "#{r:02x}{g:02x}{b:02x}"),
+        }
+    }
+}
+
+/// Graphic attributes ("pen") applied to a cell when it is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CellAttributes {
+    pub foreground: Color,
+    pub background: Color,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub blink: bool,
+    pub reverse: bool,
+    pub hidden: bool,
+    pub strikethrough: bool,
+}
+
+impl CellAttributes {
+    /// Returns true if no attribute differs from the terminal defaults
+    pub fn is_default(&self) -> bool {
+        *self == CellAttributes::default()
+    }
+}
+
+/// Formats the attributes that differ from the defaults as a comma-separated
+/// list, e.g. `fg=red,bg=#102030,bold,underline`
+impl std::fmt::Display for CellAttributes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if self.foreground != Color::Default {
+            parts.push(format!("fg={}", self.foreground));
+        }
+        if self.background != Color::Default {
+            parts.push(format!("bg={}", self.background));
+        }
+        let flags = [
+            (self.bold, "bold"),
+            (self.dim, "dim"),
+            (self.italic, "italic"),
+            (self.underline, "underline"),
+            (self.blink, "blink"),
+            (self.reverse, "reverse"),
+            (self.hidden, "hidden"),
+            (self.strikethrough, "strikethrough"),
+        ];
+        parts.extend(
+            flags
+                .iter()
+                .filter(|(enabled, _)| *enabled)
+                .map(|(_, name)| name.to_string()),
+        );
+        write!(f, "{}", parts.join(","))
+    }
+}
+
+/// Target of an OSC 8 hyperlink
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hyperlink {
+    pub uri: String,
+    /// The `id` parameter, which tells that separate runs of text (e.g. a
+    /// link wrapped over several lines) are the same link
+    pub id: Option<String>,
+}
+
+/// A run of cells on one line of the screen written with the same hyperlink
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperlinkSpan {
+    pub hyperlink: Hyperlink,
+    /// Zero-based row of the run
+    pub row: usize,
+    /// Zero-based columns covered by the run
+    pub columns: Range<usize>,
+    /// The text shown in the run
+    pub text: String,
+}
+
+/// A single character cell of the terminal screen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cell {
+    /// The base character of the grapheme cluster shown in the cell
+    pub ch: char,
+    /// Zero-width characters (combining marks, variation selectors, joined
+    /// emoji...) that follow `ch` in the same grapheme cluster
+    pub combining: Vec<char>,
+    pub attributes: CellAttributes,
+    /// Number of columns taken by the character: 2 for the first cell of a
+    /// wide (CJK, emoji) character and 0 for the continuation cell after it
+    pub width: u8,
+    /// The OSC 8 hyperlink active when the character was written
+    pub hyperlink: Option<Arc<Hyperlink>>,
+}
+
+impl Cell {
+    pub fn new(ch: char, attributes: CellAttributes) -> Self {
+        Self {
+            ch,
+            combining: Vec::new(),
+            attributes,
+            width: 1,
+            hyperlink: None,
+        }
+    }
+
+    /// The first cell of a character occupying two columns
+    pub fn wide(ch: char, attributes: CellAttributes) -> Self {
+        Self {
+            ch,
+            combining: Vec::new(),
+            attributes,
+            width: 2,
+            hyperlink: None,
+        }
+    }
+
+    /// The cell covered by the right half of a wide character
+    pub fn continuation(attributes: CellAttributes) -> Self {
+        Self {
+            ch: ' ',
+            combining: Vec::new(),
+            attributes,
+            width: 0,
+            hyperlink: None,
+        }
+    }
+
+    pub fn with_hyperlink(mut self, hyperlink: Option<Arc<Hyperlink>>) -> Self {
+        self.hyperlink = hyperlink;
+        self
+    }
+
+    pub fn is_continuation(&self) -> bool {
+        self.width == 0
+    }
+
+    /// Returns the full grapheme cluster: the base character followed by its
+    /// combining characters
+    pub fn grapheme(&self) -> String {
+        std::iter::once(self.ch)
+            .chain(self.combining.iter().copied())
+            .collect()
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self::new(' ', CellAttributes::default())
+    }
+}
@@ -0,0 +1,86 @@
+/// Character sets that can be designated into G0/G1 with `ESC ( X` / `ESC ) X`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Charset {
+    /// US ASCII (`B`)
+    #[default]
+    Ascii,
+    /// United Kingdom (`A`): `#` is replaced by the pound sign
+    Uk,
+    /// DEC Special Graphics (`0`), used for line drawing
+    DecSpecialGraphics,
+}
+
+impl Charset {
+    pub fn from_designator(designator: char) -> Option<Self> {
+        match designator {
+            'B' => Some(Charset::Ascii),
+            'A' => Some(Charset::Uk),
+            '0' => Some(Charset::DecSpecialGraphics),
+            _ => None,
+        }
+    }
+
+    pub fn translate(self, ch: char) -> char {
+        match self {
+            Charset::Ascii => ch,
+            Charset::Uk => match ch {
+                '#' => '£',
+                _ => ch,
+            },
+            Charset::DecSpecialGraphics => match ch {
+                '_' => ' ',
+                '`' => '◆',
+                'a' => '▒',
+                'b' => '␉',
+                'c' => '␌',
+                'd' => '␍',
+                'e' => '␊',
+                'f' => '°',
+                'g' => '±',
+                'h' => '␤',
+                'i' => '␋',
+                'j' => '┘',
+                'k' => '┐',
+                'l' => '┌',
+                'm' => '└',
+                'n' => '┼',
+                'o' => '⎺',
+                'p' => '⎻',
+                'q' => '─',
+                'r' => '⎼',
+                's' => '⎽',
+                't' => '├',
+                'u' => '┤',
+                'v' => '┴',
+                'w' => '┬',
+                'x' => '│',
+                'y' => '≤',
+                'z' => '≥',
+                '{' => 'π',
+                '|' => '≠',
+                '}' => '£',
+                '~' => '·',
+                _ => ch,
+            },
+        }
+    }
+}
+
+/// The G0/G1 designations and which of them is invoked by SI/SO
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CharsetState {
+    pub g0: Charset,
+    pub g1: Charset,
+    /// Set by SO (shift out) to print with G1, cleared by SI (shift in)
+    pub shifted_out: bool,
+}
+
+impl CharsetState {
+    pub fn translate(&self, ch: char) -> char {
+        if self.shifted_out {
+            self.g1.translate(ch)
+        } else {
+            self.g0.translate(ch)
+        }
+    }
+}
@@ -0,0 +1,99 @@
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Selection targeted by OSC 52 when the request leaves it empty, like xterm
+pub const DEFAULT_SELECTION: &str = "s0";
+
+/// A copy request received through OSC 52
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardWrite {
+    /// The selection parameter of the request: `c` (clipboard), `p`
+    /// (primary), `q`, `s` (select) or `0`-`7` (cut buffers), possibly several
+    pub selection: String,
+    /// The decoded text, empty when the request cleared the selection
+    pub content: String,
+}
+
+/// In-memory clipboard written by OSC 52. All selection targets share the
+/// same content.
+#[derive(Debug, Clone, Default)]
+pub struct Clipboard {
+    pub content: String,
+    /// Every write received, oldest first
+    pub history: Vec<ClipboardWrite>,
+}
+
+impl Clipboard {
+    pub fn write(&mut self, selection: &str, content: String) {
+        self.content = content.clone();
+        self.history.push(ClipboardWrite {
+            selection: selection.to_string(),
+            content,
+        });
+    }
+
+    /// The OSC 52 reply to a `?` query, carrying the current content
+    pub fn query_response(&self, selection: &str) -> String {
+        let data = encode_base64(self.content.as_bytes());
+        format!("\x1b]52;{selection};{data}\x1b\\")
+    }
+}
+
+/// Decodes standard base64, with or without padding. Returns `None` if the
+/// input contains anything else.
+pub fn decode_base64(input: &str) -> Option<Vec<u8>> {
+    let input = input.trim_end_matches('=');
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for byte in input.bytes() {
+        let value = BASE64_ALPHABET.iter().position(|&ch| ch == byte)? as u32;
+        buffer = (buffer << 6) | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+        }
+    }
+    // A single leftover character cannot encode a whole byte
+    (bits < 6).then_some(output)
+}
+
+pub fn encode_base64(input: &[u8]) -> String {
+    let mut output = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let buffer = chunk.iter().enumerate().fold(0u32, |buffer, (i, &byte)| {
+            buffer | (byte as u32) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (buffer >> (18 - 6 * i)) & 0x3f;
+                output.push(BASE64_ALPHABET[index as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_round_trip() {
+        for text in ["", "a", "ab", "abc", "abcd", "héllo 日本"] {
+            let encoded = encode_base64(text.as_bytes());
+            assert_eq!(decode_base64(&encoded).unwrap(), text.as_bytes());
+        }
+        assert_eq!(encode_base64(b"hello"), "aGVsbG8=");
+        assert_eq!(decode_base64("aGVsbG8").unwrap(), b"hello");
+    }
+
+    #[test]
+    fn test_base64_rejects_invalid_input() {
+        assert_eq!(decode_base64("aGV*bG8="), None);
+        assert_eq!(decode_base64("aGVsb"), None);
+    }
+}
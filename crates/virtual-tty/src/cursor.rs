@@ -1,68 +1,100 @@
+use crate::cell::CellAttributes;
+use crate::charset::CharsetState;
+
 pub struct Cursor {
     pub row: usize,
     pub col: usize,
+    /// Set when a character was written in the last column with autowrap
+    /// enabled: the cursor stays there and the next printed character wraps
+    /// first. Any cursor movement clears it.
+    pub pending_wrap: bool,
 }
 
 impl Cursor {
     pub fn new() -> Self {
-        Self { row: 0, col: 0 }
+        Self {
+            row: 0,
+            col: 0,
+            pending_wrap: false,
+        }
     }
 
-    pub fn move_up(&mut self, n: usize) {
-        self.row = self.row.saturating_sub(n);
+    /// Moves up `n` lines, stopping at line `top`
+    pub fn move_up(&mut self, n: usize, top: usize) {
+        self.pending_wrap = false;
+        self.row = self.row.saturating_sub(n).max(top);
     }
 
     pub fn move_down(&mut self, n: usize, max_height: usize) {
-        self.row = (self.row + n).min(max_height - 1);
+        self.pending_wrap = false;
+        self.row = self.row.saturating_add(n).min(max_height - 1);
     }
 
     pub fn move_forward(&mut self, n: usize, max_width: usize) {
-        self.col = (self.col + n).min(max_width - 1);
+        self.pending_wrap = false;
+        self.col = self.col.saturating_add(n).min(max_width - 1);
     }
 
-    pub fn move_back(&mut self, n: usize) {
-        self.col = self.col.saturating_sub(n);
+    /// Moves left `n` columns, stopping at column `left`
+    pub fn move_back(&mut self, n: usize, left: usize) {
+        self.pending_wrap = false;
+        self.col = self.col.saturating_sub(n).max(left);
     }
 
     pub fn set_position(&mut self, row: usize, col: usize, max_height: usize, max_width: usize) {
-        self.row = row.min(max_height - 1);
-        self.col = col.min(max_width - 1);
+        self.pending_wrap = false;
+        self.row = row.min(max_height.saturating_sub(1));
+        self.col = col.min(max_width.saturating_sub(1));
     }
 
-    pub fn carriage_return(&mut self) {
-        self.col = 0;
+    /// Moves down one line keeping the column. Returns true if the cursor is on
+    /// the bottom margin of the scroll region, which must then be scrolled up.
+    pub fn index(&mut self, scroll_bottom: usize, max_height: usize) -> bool {
+        self.pending_wrap = false;
+        if self.row == scroll_bottom {
+            true // Indicates scrolling is needed
+        } else {
+            // Below the scroll region the cursor stops at the last line
+            self.row = (self.row + 1).min(max_height - 1);
+            false
+        }
     }
 
-    pub fn newline(&mut self, max_height: usize) -> bool {
-        self.col = 0;
-        self.row += 1;
-        if self.row >= max_height {
-            self.row = max_height - 1;
+    /// Moves up one line keeping the column. Returns true if the cursor is on
+    /// the top margin of the scroll region, which must then be scrolled down.
+    pub fn reverse_index(&mut self, scroll_top: usize) -> bool {
+        self.pending_wrap = false;
+        if self.row == scroll_top {
             true // Indicates scrolling is needed
         } else {
+            // Above the scroll region the cursor stops at the first line
+            self.row = self.row.saturating_sub(1);
             false
         }
     }
 
     pub fn backspace(&mut self) {
+        self.pending_wrap = false;
         if self.col > 0 {
             self.col -= 1;
         }
     }
 
-    pub fn advance(&mut self, max_width: usize, max_height: usize) -> bool {
-        self.col += 1;
-        if self.col >= max_width {
-            self.col = 0;
-            self.row += 1;
-            if self.row >= max_height {
-                self.row = max_height - 1;
-                true // Indicates scrolling is needed
-            } else {
-                false
-            }
+    /// Moves to the given column of the current line
+    pub fn set_col(&mut self, col: usize) {
+        self.pending_wrap = false;
+        self.col = col;
+    }
+
+    /// Moves past `n` columns that were just written at the cursor. At the
+    /// right margin the cursor stays on the last column and, with autowrap
+    /// enabled, the wrap is deferred until the next printed character.
+    pub fn advance(&mut self, n: usize, max_width: usize, autowrap: bool) {
+        if self.col + n < max_width {
+            self.col += n;
         } else {
-            false
+            self.col = max_width - 1;
+            self.pending_wrap = autowrap;
         }
     }
 
@@ -70,3 +102,15 @@ impl Cursor {
         (self.row, self.col)
     }
 }
+
+/// Cursor state saved by DECSC (`ESC 7`, `CSI s`, `CSI ? 1049 h`) and
+/// restored by DECRC
+#[derive(Debug, Clone, Default)]
+pub struct SavedCursor {
+    pub row: usize,
+    pub col: usize,
+    pub pending_wrap: bool,
+    pub origin_mode: bool,
+    pub pen: CellAttributes,
+    pub charsets: CharsetState,
+}
@@ -0,0 +1,45 @@
+/// What the terminal reports about itself when a program asks with DA1
+/// (`CSI c`), DA2 (`CSI > c`) or XTVERSION (`CSI > q`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceIdentity {
+    /// Parameters of the DA1 reply `CSI ? Ps ; ... c`: the conformance level
+    /// followed by the supported extensions
+    pub primary_attributes: Vec<usize>,
+    /// First parameter of the DA2 reply `CSI > Pp ; Pv ; 0 c`, the terminal type
+    pub terminal_type: usize,
+    /// Second parameter of the DA2 reply, the firmware version
+    pub firmware_version: usize,
+    /// Name and version sent in the XTVERSION reply `DCS > | text ST`
+    pub name: String,
+}
+
+impl Default for DeviceIdentity {
+    /// A VT220 with ANSI colors (`CSI ? 62 ; 22 c`) named after this crate
+    fn default() -> Self {
+        Self {
+            primary_attributes: vec![62, 22],
+            terminal_type: 1,
+            firmware_version: 0,
+            name: format!("virtual-tty({})", env!("CARGO_PKG_VERSION")),
+        }
+    }
+}
+
+impl DeviceIdentity {
+    pub fn primary_attributes_response(&self) -> String {
+        let params: Vec<String> = self
+            .primary_attributes
+            .iter()
+            .map(|param| param.to_string())
+            .collect();
+        format!("\x1b[?{}c", params.join(";"))
+    }
+
+    pub fn secondary_attributes_response(&self) -> String {
+        format!("\x1b[>{};{};0c", self.terminal_type, self.firmware_version)
+    }
+
+    pub fn version_response(&self) -> String {
+        format!("\x1bP>|{}\x1b\\", self.name)
+    }
+}
@@ -1,5 +1,7 @@
+#[allow(dead_code)]
 pub type Result<T> = std::result::Result<T, VirtualTtyError>;
 
+#[allow(dead_code)]
 #[derive(Debug)]
 pub enum VirtualTtyError {
     InvalidEscapeSequence(String),
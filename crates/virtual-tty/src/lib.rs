@@ -3,12 +3,30 @@ use std::sync::{Arc, Mutex};
 
 mod ansi;
 mod buffer;
+mod cell;
+mod charset;
+mod clipboard;
 mod cursor;
+mod device;
 mod errors;
+mod notification;
+mod progress;
+mod semantic;
 mod state;
-
-use ansi::{parse_escape_sequence, AnsiCommand, AnsiParser, ClearMode, ControlChar, Token};
+mod tabs;
+mod title;
+mod utf8;
+mod width;
+
+pub use cell::{Cell, CellAttributes, Color, Hyperlink, HyperlinkSpan};
+pub use clipboard::ClipboardWrite;
+pub use device::DeviceIdentity;
+pub use notification::Notification;
+pub use progress::{Progress, ProgressState};
+pub use semantic::SemanticCommand;
+pub use state::Screen;
 use state::TtyState;
+pub use title::SavedTitle;
 
 pub struct VirtualTty {
     state: Arc<Mutex<TtyState>>,
@@ -24,19 +42,29 @@ pub struct VirtualTtyStreams {
 
 pub struct VirtualTtyStdout {
     state: Arc<Mutex<TtyState>>,
-    width: usize,
-    height: usize,
 }
 
 pub struct VirtualTtyStderr {
     state: Arc<Mutex<TtyState>>,
-    width: usize,
-    height: usize,
 }
 
+/// Number of lines kept in the scrollback by `VirtualTty::new`
+pub const DEFAULT_SCROLLBACK_LIMIT: usize = 1000;
+
 impl VirtualTty {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(width: usize, height: usize) -> VirtualTtyStreams {
-        let state = TtyState::new(width, height);
+        Self::with_scrollback_limit(width, height, DEFAULT_SCROLLBACK_LIMIT)
+    }
+
+    /// Creates a terminal that keeps at most `scrollback_limit` lines scrolled
+    /// off the top of the screen. A limit of 0 disables the scrollback.
+    pub fn with_scrollback_limit(
+        width: usize,
+        height: usize,
+        scrollback_limit: usize,
+    ) -> VirtualTtyStreams {
+        let state = TtyState::new(width, height, scrollback_limit);
         let shared_state = Arc::new(Mutex::new(state));
 
         let tty = VirtualTty {
@@ -48,13 +76,9 @@ impl VirtualTty {
         VirtualTtyStreams {
             stdout: VirtualTtyStdout {
                 state: shared_state.clone(),
-                width,
-                height,
             },
             stderr: VirtualTtyStderr {
                 state: shared_state.clone(),
-                width,
-                height,
             },
             tty,
         }
@@ -77,152 +101,191 @@ impl VirtualTty {
         state.get_snapshot()
     }
 
+    /// Returns a snapshot in the same layout as `get_snapshot`, with runs of
+    /// styled text wrapped in attribute markers such as `[fg=red,bold]Error[/]`
+    pub fn get_styled_snapshot(&self) -> String {
+        let state = self.state.lock().unwrap();
+        state.get_styled_snapshot()
+    }
+
+    /// Returns which screen is currently displayed
+    pub fn active_screen(&self) -> Screen {
+        let state = self.state.lock().unwrap();
+        state.active_screen
+    }
+
+    pub fn is_alternate_screen(&self) -> bool {
+        self.active_screen() == Screen::Alternate
+    }
+
+    /// Returns a snapshot of the given screen, whether or not it is currently
+    /// displayed
+    pub fn get_screen_snapshot(&self, screen: Screen) -> String {
+        let state = self.state.lock().unwrap();
+        state.get_screen_snapshot(screen)
+    }
+
+    /// Returns the lines scrolled off the top of the primary screen, oldest
+    /// first, in the same layout as `get_snapshot`
+    pub fn get_scrollback_snapshot(&self) -> String {
+        let state = self.state.lock().unwrap();
+        state.get_scrollback_snapshot()
+    }
+
+    /// Returns the scrollback followed by the primary screen, i.e. everything
+    /// written to the terminal that is still retained
+    pub fn get_history_snapshot(&self) -> String {
+        let state = self.state.lock().unwrap();
+        state.get_history_snapshot()
+    }
+
+    pub fn get_scrollback_len(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.get_scrollback_len()
+    }
+
+    /// Changes the scrollback limit, dropping the oldest lines if the
+    /// scrollback is already longer
+    pub fn set_scrollback_limit(&mut self, limit: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.set_scrollback_limit(limit);
+    }
+
+    pub fn clear_scrollback(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        state.clear_scrollback();
+    }
+
     pub fn clear(&mut self) {
         let mut state = self.state.lock().unwrap();
         state.clear(self.width, self.height);
     }
 
+    /// Changes the size of the terminal. The content is kept from the top-left
+    /// corner; the scroll region and tab stops are reset.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.resize(width, height);
+        self.width = width;
+        self.height = height;
+    }
+
+    /// Returns the zero-based columns that currently have a tab stop
+    pub fn get_tab_stops(&self) -> Vec<usize> {
+        let state = self.state.lock().unwrap();
+        state.get_tab_stops()
+    }
+
     pub fn get_cursor_position(&self) -> (usize, usize) {
         let state = self.state.lock().unwrap();
         state.get_cursor_position()
     }
 
-    fn process_token(&self, token: Token, state: &mut TtyState) {
-        match token {
-            Token::Text(text) => {
-                for ch in text.chars() {
-                    let cursor_row = state.cursor.row;
-                    let cursor_col = state.cursor.col;
-                    if cursor_row < self.height && cursor_col < self.width {
-                        state.buffer.set_char(cursor_row, cursor_col, ch);
-                        if state.cursor.advance(self.width, self.height) {
-                            state.buffer.scroll_up();
-                        }
-                    }
-                }
-            }
-            Token::Command(command) => {
-                // Validate command before executing
-                if command.validate().is_ok() {
-                    self.execute_ansi_command(&command, state);
-                }
-                // If validation fails, silently ignore the command
-            }
-            Token::ControlChar(ctrl_char) => {
-                match ctrl_char {
-                    ControlChar::LineFeed => {
-                        if state.cursor.newline(self.height) {
-                            state.buffer.scroll_up();
-                        }
-                    }
-                    ControlChar::CarriageReturn => {
-                        state.cursor.carriage_return();
-                    }
-                    ControlChar::Backspace => {
-                        state.cursor.backspace();
-                    }
-                    ControlChar::Tab => {
-                        // Simple tab handling - advance to next tab stop (8 chars)
-                        let tab_width = 8;
-                        let cursor_col = state.cursor.col;
-                        let next_tab_stop = ((cursor_col / tab_width) + 1) * tab_width;
-                        let spaces_to_add = next_tab_stop - cursor_col;
-                        for _ in 0..spaces_to_add {
-                            let cursor_row = state.cursor.row;
-                            let cursor_col = state.cursor.col;
-                            if cursor_row < self.height && cursor_col < self.width {
-                                state.buffer.set_char(cursor_row, cursor_col, ' ');
-                                if state.cursor.advance(self.width, self.height) {
-                                    state.buffer.scroll_up();
-                                }
-                            }
-                        }
-                    }
-                    ControlChar::Bell => {
-                        // Bell character - typically ignored in terminal emulation
-                    }
-                    ControlChar::VerticalTab => {
-                        // Vertical tab - move to next line
-                        if state.cursor.newline(self.height) {
-                            state.buffer.scroll_up();
-                        }
-                    }
-                    ControlChar::FormFeed => {
-                        // Form feed - clear screen and move to top
-                        state.buffer.clear();
-                        state.cursor.set_position(0, 0, self.height, self.width);
-                    }
-                }
-            }
-            Token::Invalid(_) => {
-                // Ignore invalid tokens for now
-            }
-        }
+    /// Returns the character and graphic attributes of the cell at the given
+    /// zero-based position, or `None` if it lies outside the screen
+    pub fn cell_at(&self, row: usize, col: usize) -> Option<Cell> {
+        let state = self.state.lock().unwrap();
+        state.get_cell(row, col)
     }
 
-    fn execute_ansi_command(&self, command: &AnsiCommand, state: &mut TtyState) {
-        match command {
-            AnsiCommand::CursorUp(n) => {
-                state.cursor.move_up(*n);
-            }
-            AnsiCommand::CursorDown(n) => {
-                state.cursor.move_down(*n, self.height);
-            }
-            AnsiCommand::CursorForward(n) => {
-                state.cursor.move_forward(*n, self.width);
-            }
-            AnsiCommand::CursorBack(n) => {
-                state.cursor.move_back(*n);
-            }
-            AnsiCommand::CursorPosition { row, col } => {
-                state
-                    .cursor
-                    .set_position(*row, *col, self.height, self.width);
-            }
-            AnsiCommand::ClearScreen(clear_mode) => match clear_mode {
-                ClearMode::Entire => {
-                    state.buffer.clear();
-                    state.cursor.set_position(0, 0, self.height, self.width);
-                }
-                ClearMode::ToBeginning => {
-                    let cursor_row = state.cursor.row;
-                    let cursor_col = state.cursor.col;
-                    state
-                        .buffer
-                        .clear_from_beginning_to_cursor(cursor_row, cursor_col);
-                }
-                ClearMode::ToEnd => {
-                    let cursor_row = state.cursor.row;
-                    let cursor_col = state.cursor.col;
-                    state
-                        .buffer
-                        .clear_from_cursor_to_end(cursor_row, cursor_col);
-                }
-            },
-            AnsiCommand::ClearLine(clear_mode) => match clear_mode {
-                ClearMode::Entire => {
-                    let cursor_row = state.cursor.row;
-                    state.buffer.clear_entire_line(cursor_row);
-                }
-                ClearMode::ToBeginning => {
-                    let cursor_row = state.cursor.row;
-                    let cursor_col = state.cursor.col;
-                    state
-                        .buffer
-                        .clear_line_from_beginning_to_cursor(cursor_row, cursor_col);
-                }
-                ClearMode::ToEnd => {
-                    let cursor_row = state.cursor.row;
-                    let cursor_col = state.cursor.col;
-                    state
-                        .buffer
-                        .clear_line_from_cursor_to_end(cursor_row, cursor_col);
-                }
-            },
-            AnsiCommand::SetGraphicsRendition => {
-                // SGR (Select Graphic Rendition) - ignore for now
-            }
-        }
+    /// Returns the runs of text written while an OSC 8 hyperlink was active,
+    /// one per line, from the top of the screen
+    pub fn get_hyperlinks(&self) -> Vec<HyperlinkSpan> {
+        let state = self.state.lock().unwrap();
+        state.get_hyperlinks()
+    }
+
+    /// Returns the commands run at prompts marked with OSC 133 (shell
+    /// integration), oldest first, with the text of their prompt, command line
+    /// and output read from the screen and scrollback
+    pub fn get_commands(&self) -> Vec<SemanticCommand> {
+        let state = self.state.lock().unwrap();
+        state.get_commands()
+    }
+
+    /// Returns the most recent command marked with OSC 133
+    pub fn get_last_command(&self) -> Option<SemanticCommand> {
+        self.get_commands().pop()
+    }
+
+    /// Returns the working directory last reported by the shell with OSC 7
+    pub fn get_working_directory(&self) -> Option<String> {
+        let state = self.state.lock().unwrap();
+        state.working_directory.clone()
+    }
+
+    /// Returns the desktop notifications sent with OSC 9 or OSC 777, oldest first
+    pub fn get_notifications(&self) -> Vec<Notification> {
+        let state = self.state.lock().unwrap();
+        state.notifications.clone()
+    }
+
+    /// Returns the progress indicator last set with `OSC 9;4`
+    pub fn get_progress(&self) -> Progress {
+        let state = self.state.lock().unwrap();
+        state.progress
+    }
+
+    /// Returns every progress update received, oldest first
+    pub fn get_progress_history(&self) -> Vec<Progress> {
+        let state = self.state.lock().unwrap();
+        state.progress_history.clone()
+    }
+
+    /// Returns the text last copied with OSC 52 or set with `set_clipboard`
+    pub fn get_clipboard(&self) -> String {
+        let state = self.state.lock().unwrap();
+        state.clipboard.content.clone()
+    }
+
+    /// Sets the clipboard content, which OSC 52 queries (`ESC]52;c;?`) answer with
+    pub fn set_clipboard(&mut self, content: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.clipboard.content = content.to_string();
+    }
+
+    /// Returns every OSC 52 write received, oldest first
+    pub fn get_clipboard_history(&self) -> Vec<ClipboardWrite> {
+        let state = self.state.lock().unwrap();
+        state.clipboard.history.clone()
+    }
+
+    /// Removes and returns the replies the terminal sent to queries (DSR,
+    /// CPR, DA1, DA2, XTVERSION, OSC 52), oldest first. A real terminal writes
+    /// them to the program's input.
+    pub fn take_responses(&mut self) -> Vec<String> {
+        let mut state = self.state.lock().unwrap();
+        std::mem::take(&mut state.responses)
+    }
+
+    /// Returns what the terminal reports to DA1, DA2 and XTVERSION queries
+    pub fn get_device_identity(&self) -> DeviceIdentity {
+        let state = self.state.lock().unwrap();
+        state.identity.clone()
+    }
+
+    /// Changes what the terminal reports to DA1, DA2 and XTVERSION queries
+    pub fn set_device_identity(&mut self, identity: DeviceIdentity) {
+        let mut state = self.state.lock().unwrap();
+        state.identity = identity;
+    }
+
+    /// Returns the window title set by `OSC 0` or `OSC 2`
+    pub fn get_title(&self) -> String {
+        let state = self.state.lock().unwrap();
+        state.titles.title.clone()
+    }
+
+    /// Returns the icon name set by `OSC 0` or `OSC 1`
+    pub fn get_icon_name(&self) -> String {
+        let state = self.state.lock().unwrap();
+        state.titles.icon_name.clone()
+    }
+
+    /// Returns the titles saved with `CSI 22 t`, oldest first
+    pub fn get_title_stack(&self) -> Vec<SavedTitle> {
+        let state = self.state.lock().unwrap();
+        state.titles.stack.clone()
     }
 }
 
@@ -243,72 +306,135 @@ impl VirtualTtyStreams {
         self.tty.get_snapshot()
     }
 
+    pub fn get_styled_snapshot(&self) -> String {
+        self.tty.get_styled_snapshot()
+    }
+
+    pub fn active_screen(&self) -> Screen {
+        self.tty.active_screen()
+    }
+
+    pub fn is_alternate_screen(&self) -> bool {
+        self.tty.is_alternate_screen()
+    }
+
+    pub fn get_screen_snapshot(&self, screen: Screen) -> String {
+        self.tty.get_screen_snapshot(screen)
+    }
+
+    pub fn get_scrollback_snapshot(&self) -> String {
+        self.tty.get_scrollback_snapshot()
+    }
+
+    pub fn get_history_snapshot(&self) -> String {
+        self.tty.get_history_snapshot()
+    }
+
+    pub fn get_scrollback_len(&self) -> usize {
+        self.tty.get_scrollback_len()
+    }
+
+    pub fn set_scrollback_limit(&mut self, limit: usize) {
+        self.tty.set_scrollback_limit(limit)
+    }
+
+    pub fn clear_scrollback(&mut self) {
+        self.tty.clear_scrollback()
+    }
+
     pub fn clear(&mut self) {
         self.tty.clear()
     }
 
+    pub fn resize(&mut self, width: usize, height: usize) {
+        self.tty.resize(width, height)
+    }
+
+    pub fn get_tab_stops(&self) -> Vec<usize> {
+        self.tty.get_tab_stops()
+    }
+
     pub fn get_cursor_position(&self) -> (usize, usize) {
         self.tty.get_cursor_position()
     }
 
-    pub fn send_input(&mut self, input: &str) {
-        // For input, we'll use the same logic as the original send_input
-        let data = input;
-        match AnsiParser::parse(data) {
-            Ok(tokens) => {
-                let mut state = self.tty.state.lock().unwrap();
-                for token in tokens {
-                    self.tty.process_token(token, &mut state);
-                }
-            }
-            Err(_) => {
-                // Fallback to legacy parsing for compatibility
-                self.send_input_legacy(input);
-            }
-        }
+    pub fn cell_at(&self, row: usize, col: usize) -> Option<Cell> {
+        self.tty.cell_at(row, col)
+    }
+
+    pub fn get_hyperlinks(&self) -> Vec<HyperlinkSpan> {
+        self.tty.get_hyperlinks()
+    }
+
+    pub fn get_commands(&self) -> Vec<SemanticCommand> {
+        self.tty.get_commands()
+    }
+
+    pub fn get_last_command(&self) -> Option<SemanticCommand> {
+        self.tty.get_last_command()
     }
 
-    fn send_input_legacy(&mut self, data: &str) {
+    pub fn get_working_directory(&self) -> Option<String> {
+        self.tty.get_working_directory()
+    }
+
+    pub fn get_notifications(&self) -> Vec<Notification> {
+        self.tty.get_notifications()
+    }
+
+    pub fn get_progress(&self) -> Progress {
+        self.tty.get_progress()
+    }
+
+    pub fn get_progress_history(&self) -> Vec<Progress> {
+        self.tty.get_progress_history()
+    }
+
+    pub fn get_clipboard(&self) -> String {
+        self.tty.get_clipboard()
+    }
+
+    pub fn set_clipboard(&mut self, content: &str) {
+        self.tty.set_clipboard(content)
+    }
+
+    pub fn get_clipboard_history(&self) -> Vec<ClipboardWrite> {
+        self.tty.get_clipboard_history()
+    }
+
+    pub fn take_responses(&mut self) -> Vec<String> {
+        self.tty.take_responses()
+    }
+
+    pub fn get_device_identity(&self) -> DeviceIdentity {
+        self.tty.get_device_identity()
+    }
+
+    pub fn set_device_identity(&mut self, identity: DeviceIdentity) {
+        self.tty.set_device_identity(identity)
+    }
+
+    pub fn get_title(&self) -> String {
+        self.tty.get_title()
+    }
+
+    pub fn get_icon_name(&self) -> String {
+        self.tty.get_icon_name()
+    }
+
+    pub fn get_title_stack(&self) -> Vec<SavedTitle> {
+        self.tty.get_title_stack()
+    }
+
+    pub fn send_input(&mut self, input: &str) {
         let mut state = self.tty.state.lock().unwrap();
-        let mut chars = data.chars();
-        while let Some(ch) = chars.next() {
-            if ch == '\x1b' {
-                // Start of escape sequence
-                if chars.next() == Some('[') {
-                    if let Some(command) = parse_escape_sequence(&mut chars) {
-                        self.tty.execute_ansi_command(&command, &mut state);
-                    }
-                }
-            } else if ch == '\r' {
-                // Carriage return
-                state.cursor.carriage_return();
-            } else if ch == '\n' {
-                // Newline
-                if state.cursor.newline(self.tty.height) {
-                    state.buffer.scroll_up();
-                }
-            } else if ch == '\x08' {
-                // Backspace
-                state.cursor.backspace();
-            } else {
-                // Regular character
-                let cursor_row = state.cursor.row;
-                let cursor_col = state.cursor.col;
-                if cursor_row < self.tty.height && cursor_col < self.tty.width {
-                    state.buffer.set_char(cursor_row, cursor_col, ch);
-                    if state.cursor.advance(self.tty.width, self.tty.height) {
-                        state.buffer.scroll_up();
-                    }
-                }
-            }
-        }
+        state.write_bytes(input.as_bytes());
     }
 }
 
 impl Write for VirtualTtyStdout {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        let data = String::from_utf8_lossy(buf);
-        self.write_internal(&data);
+        self.write_internal(buf);
         Ok(buf.len())
     }
 
@@ -319,8 +445,7 @@ impl Write for VirtualTtyStdout {
 
 impl Write for VirtualTtyStderr {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        let data = String::from_utf8_lossy(buf);
-        self.write_internal(&data);
+        self.write_internal(buf);
         Ok(buf.len())
     }
 
@@ -330,386 +455,16 @@ impl Write for VirtualTtyStderr {
 }
 
 impl VirtualTtyStdout {
-    fn write_internal(&mut self, data: &str) {
-        // Use the new tokenized parser
-        match AnsiParser::parse(data) {
-            Ok(tokens) => {
-                let mut state = self.state.lock().unwrap();
-                for token in tokens {
-                    self.process_token(token, &mut state);
-                }
-            }
-            Err(_) => {
-                // Fallback to legacy parsing for compatibility
-                self.write_internal_legacy(data);
-            }
-        }
-    }
-
-    fn process_token(&self, token: Token, state: &mut TtyState) {
-        match token {
-            Token::Text(text) => {
-                for ch in text.chars() {
-                    let cursor_row = state.cursor.row;
-                    let cursor_col = state.cursor.col;
-                    if cursor_row < self.height && cursor_col < self.width {
-                        state.buffer.set_char(cursor_row, cursor_col, ch);
-                        if state.cursor.advance(self.width, self.height) {
-                            state.buffer.scroll_up();
-                        }
-                    }
-                }
-            }
-            Token::Command(command) => {
-                // Validate command before executing
-                if command.validate().is_ok() {
-                    self.execute_ansi_command(&command, state);
-                }
-                // If validation fails, silently ignore the command
-            }
-            Token::ControlChar(ctrl_char) => {
-                match ctrl_char {
-                    ControlChar::LineFeed => {
-                        if state.cursor.newline(self.height) {
-                            state.buffer.scroll_up();
-                        }
-                    }
-                    ControlChar::CarriageReturn => {
-                        state.cursor.carriage_return();
-                    }
-                    ControlChar::Backspace => {
-                        state.cursor.backspace();
-                    }
-                    ControlChar::Tab => {
-                        // Simple tab handling - advance to next tab stop (8 chars)
-                        let tab_width = 8;
-                        let cursor_col = state.cursor.col;
-                        let next_tab_stop = ((cursor_col / tab_width) + 1) * tab_width;
-                        let spaces_to_add = next_tab_stop - cursor_col;
-                        for _ in 0..spaces_to_add {
-                            let cursor_row = state.cursor.row;
-                            let cursor_col = state.cursor.col;
-                            if cursor_row < self.height && cursor_col < self.width {
-                                state.buffer.set_char(cursor_row, cursor_col, ' ');
-                                if state.cursor.advance(self.width, self.height) {
-                                    state.buffer.scroll_up();
-                                }
-                            }
-                        }
-                    }
-                    ControlChar::Bell => {
-                        // Bell character - typically ignored in terminal emulation
-                    }
-                    ControlChar::VerticalTab => {
-                        // Vertical tab - move to next line
-                        if state.cursor.newline(self.height) {
-                            state.buffer.scroll_up();
-                        }
-                    }
-                    ControlChar::FormFeed => {
-                        // Form feed - clear screen and move to top
-                        state.buffer.clear();
-                        state.cursor.set_position(0, 0, self.height, self.width);
-                    }
-                }
-            }
-            Token::Invalid(_) => {
-                // Ignore invalid tokens for now
-            }
-        }
-    }
-
-    fn write_internal_legacy(&mut self, data: &str) {
+    fn write_internal(&mut self, data: &[u8]) {
         let mut state = self.state.lock().unwrap();
-        let mut chars = data.chars();
-        while let Some(ch) = chars.next() {
-            if ch == '\x1b' {
-                // Start of escape sequence
-                if chars.next() == Some('[') {
-                    if let Some(command) = parse_escape_sequence(&mut chars) {
-                        self.execute_ansi_command(&command, &mut state);
-                    }
-                }
-            } else if ch == '\r' {
-                // Carriage return
-                state.cursor.carriage_return();
-            } else if ch == '\n' {
-                // Newline
-                if state.cursor.newline(self.height) {
-                    state.buffer.scroll_up();
-                }
-            } else if ch == '\x08' {
-                // Backspace
-                state.cursor.backspace();
-            } else {
-                // Regular character
-                let cursor_row = state.cursor.row;
-                let cursor_col = state.cursor.col;
-                if cursor_row < self.height && cursor_col < self.width {
-                    state.buffer.set_char(cursor_row, cursor_col, ch);
-                    if state.cursor.advance(self.width, self.height) {
-                        state.buffer.scroll_up();
-                    }
-                }
-            }
-        }
-    }
-
-    fn execute_ansi_command(&self, command: &AnsiCommand, state: &mut TtyState) {
-        match command {
-            AnsiCommand::CursorUp(n) => {
-                state.cursor.move_up(*n);
-            }
-            AnsiCommand::CursorDown(n) => {
-                state.cursor.move_down(*n, self.height);
-            }
-            AnsiCommand::CursorForward(n) => {
-                state.cursor.move_forward(*n, self.width);
-            }
-            AnsiCommand::CursorBack(n) => {
-                state.cursor.move_back(*n);
-            }
-            AnsiCommand::CursorPosition { row, col } => {
-                state
-                    .cursor
-                    .set_position(*row, *col, self.height, self.width);
-            }
-            AnsiCommand::ClearScreen(clear_mode) => match clear_mode {
-                ClearMode::Entire => {
-                    state.buffer.clear();
-                    state.cursor.set_position(0, 0, self.height, self.width);
-                }
-                ClearMode::ToBeginning => {
-                    let cursor_row = state.cursor.row;
-                    let cursor_col = state.cursor.col;
-                    state
-                        .buffer
-                        .clear_from_beginning_to_cursor(cursor_row, cursor_col);
-                }
-                ClearMode::ToEnd => {
-                    let cursor_row = state.cursor.row;
-                    let cursor_col = state.cursor.col;
-                    state
-                        .buffer
-                        .clear_from_cursor_to_end(cursor_row, cursor_col);
-                }
-            },
-            AnsiCommand::ClearLine(clear_mode) => match clear_mode {
-                ClearMode::Entire => {
-                    let cursor_row = state.cursor.row;
-                    state.buffer.clear_entire_line(cursor_row);
-                }
-                ClearMode::ToBeginning => {
-                    let cursor_row = state.cursor.row;
-                    let cursor_col = state.cursor.col;
-                    state
-                        .buffer
-                        .clear_line_from_beginning_to_cursor(cursor_row, cursor_col);
-                }
-                ClearMode::ToEnd => {
-                    let cursor_row = state.cursor.row;
-                    let cursor_col = state.cursor.col;
-                    state
-                        .buffer
-                        .clear_line_from_cursor_to_end(cursor_row, cursor_col);
-                }
-            },
-            AnsiCommand::SetGraphicsRendition => {
-                // SGR (Select Graphic Rendition) - ignore for now
-            }
-        }
+        state.write_bytes(data);
     }
 }
 
 impl VirtualTtyStderr {
-    fn write_internal(&mut self, data: &str) {
-        // Use the new tokenized parser
-        match AnsiParser::parse(data) {
-            Ok(tokens) => {
-                let mut state = self.state.lock().unwrap();
-                for token in tokens {
-                    self.process_token(token, &mut state);
-                }
-            }
-            Err(_) => {
-                // Fallback to legacy parsing for compatibility
-                self.write_internal_legacy(data);
-            }
-        }
-    }
-
-    fn process_token(&self, token: Token, state: &mut TtyState) {
-        match token {
-            Token::Text(text) => {
-                for ch in text.chars() {
-                    let cursor_row = state.cursor.row;
-                    let cursor_col = state.cursor.col;
-                    if cursor_row < self.height && cursor_col < self.width {
-                        state.buffer.set_char(cursor_row, cursor_col, ch);
-                        if state.cursor.advance(self.width, self.height) {
-                            state.buffer.scroll_up();
-                        }
-                    }
-                }
-            }
-            Token::Command(command) => {
-                // Validate command before executing
-                if command.validate().is_ok() {
-                    self.execute_ansi_command(&command, state);
-                }
-                // If validation fails, silently ignore the command
-            }
-            Token::ControlChar(ctrl_char) => {
-                match ctrl_char {
-                    ControlChar::LineFeed => {
-                        if state.cursor.newline(self.height) {
-                            state.buffer.scroll_up();
-                        }
-                    }
-                    ControlChar::CarriageReturn => {
-                        state.cursor.carriage_return();
-                    }
-                    ControlChar::Backspace => {
-                        state.cursor.backspace();
-                    }
-                    ControlChar::Tab => {
-                        // Simple tab handling - advance to next tab stop (8 chars)
-                        let tab_width = 8;
-                        let cursor_col = state.cursor.col;
-                        let next_tab_stop = ((cursor_col / tab_width) + 1) * tab_width;
-                        let spaces_to_add = next_tab_stop - cursor_col;
-                        for _ in 0..spaces_to_add {
-                            let cursor_row = state.cursor.row;
-                            let cursor_col = state.cursor.col;
-                            if cursor_row < self.height && cursor_col < self.width {
-                                state.buffer.set_char(cursor_row, cursor_col, ' ');
-                                if state.cursor.advance(self.width, self.height) {
-                                    state.buffer.scroll_up();
-                                }
-                            }
-                        }
-                    }
-                    ControlChar::Bell => {
-                        // Bell character - typically ignored in terminal emulation
-                    }
-                    ControlChar::VerticalTab => {
-                        // Vertical tab - move to next line
-                        if state.cursor.newline(self.height) {
-                            state.buffer.scroll_up();
-                        }
-                    }
-                    ControlChar::FormFeed => {
-                        // Form feed - clear screen and move to top
-                        state.buffer.clear();
-                        state.cursor.set_position(0, 0, self.height, self.width);
-                    }
-                }
-            }
-            Token::Invalid(_) => {
-                // Ignore invalid tokens for now
-            }
-        }
-    }
-
-    fn write_internal_legacy(&mut self, data: &str) {
+    fn write_internal(&mut self, data: &[u8]) {
         let mut state = self.state.lock().unwrap();
-        let mut chars = data.chars();
-        while let Some(ch) = chars.next() {
-            if ch == '\x1b' {
-                // Start of escape sequence
-                if chars.next() == Some('[') {
-                    if let Some(command) = parse_escape_sequence(&mut chars) {
-                        self.execute_ansi_command(&command, &mut state);
-                    }
-                }
-            } else if ch == '\r' {
-                // Carriage return
-                state.cursor.carriage_return();
-            } else if ch == '\n' {
-                // Newline
-                if state.cursor.newline(self.height) {
-                    state.buffer.scroll_up();
-                }
-            } else if ch == '\x08' {
-                // Backspace
-                state.cursor.backspace();
-            } else {
-                // Regular character
-                let cursor_row = state.cursor.row;
-                let cursor_col = state.cursor.col;
-                if cursor_row < self.height && cursor_col < self.width {
-                    state.buffer.set_char(cursor_row, cursor_col, ch);
-                    if state.cursor.advance(self.width, self.height) {
-                        state.buffer.scroll_up();
-                    }
-                }
-            }
-        }
-    }
-
-    fn execute_ansi_command(&self, command: &AnsiCommand, state: &mut TtyState) {
-        match command {
-            AnsiCommand::CursorUp(n) => {
-                state.cursor.move_up(*n);
-            }
-            AnsiCommand::CursorDown(n) => {
-                state.cursor.move_down(*n, self.height);
-            }
-            AnsiCommand::CursorForward(n) => {
-                state.cursor.move_forward(*n, self.width);
-            }
-            AnsiCommand::CursorBack(n) => {
-                state.cursor.move_back(*n);
-            }
-            AnsiCommand::CursorPosition { row, col } => {
-                state
-                    .cursor
-                    .set_position(*row, *col, self.height, self.width);
-            }
-            AnsiCommand::ClearScreen(clear_mode) => match clear_mode {
-                ClearMode::Entire => {
-                    state.buffer.clear();
-                    state.cursor.set_position(0, 0, self.height, self.width);
-                }
-                ClearMode::ToBeginning => {
-                    let cursor_row = state.cursor.row;
-                    let cursor_col = state.cursor.col;
-                    state
-                        .buffer
-                        .clear_from_beginning_to_cursor(cursor_row, cursor_col);
-                }
-                ClearMode::ToEnd => {
-                    let cursor_row = state.cursor.row;
-                    let cursor_col = state.cursor.col;
-                    state
-                        .buffer
-                        .clear_from_cursor_to_end(cursor_row, cursor_col);
-                }
-            },
-            AnsiCommand::ClearLine(clear_mode) => match clear_mode {
-                ClearMode::Entire => {
-                    let cursor_row = state.cursor.row;
-                    state.buffer.clear_entire_line(cursor_row);
-                }
-                ClearMode::ToBeginning => {
-                    let cursor_row = state.cursor.row;
-                    let cursor_col = state.cursor.col;
-                    state
-                        .buffer
-                        .clear_line_from_beginning_to_cursor(cursor_row, cursor_col);
-                }
-                ClearMode::ToEnd => {
-                    let cursor_row = state.cursor.row;
-                    let cursor_col = state.cursor.col;
-                    state
-                        .buffer
-                        .clear_line_from_cursor_to_end(cursor_row, cursor_col);
-                }
-            },
-            AnsiCommand::SetGraphicsRendition => {
-                // SGR (Select Graphic Rendition) - ignore for now
-            }
-        }
+        state.write_bytes(data);
     }
 }
 
@@ -744,7 +499,7 @@ mod tests {
         let snapshot = tty.get_snapshot();
         insta::assert_snapshot!(snapshot, @r"
         Line1     \n
-        Line2     \n
+             Line2\n
                   \n
         ");
     }
@@ -789,7 +544,7 @@ mod tests {
     #[test]
     fn test_scroll() {
         let mut tty = VirtualTty::new(10, 2);
-        write!(tty.stdout, "Line1\nLine2\nLine3").unwrap();
+        write!(tty.stdout, "Line1\r\nLine2\r\nLine3").unwrap();
         let snapshot = tty.get_snapshot();
         insta::assert_snapshot!(snapshot, @r"
         Line2     \n
@@ -833,7 +588,7 @@ mod tests {
         let snapshot = tty.get_snapshot();
         insta::assert_snapshot!(snapshot, @r"
         Line1     \n
-        Line2     \n
+             Line2\n
                   \n
         ");
     }
@@ -866,7 +621,7 @@ mod tests {
     #[test]
     fn test_stderr_scroll() {
         let mut tty = VirtualTty::new(10, 2);
-        write!(tty.stderr, "Line1\nLine2\nLine3").unwrap();
+        write!(tty.stderr, "Line1\r\nLine2\r\nLine3").unwrap();
         let snapshot = tty.get_snapshot();
         insta::assert_snapshot!(snapshot, @r"
         Line2     \n
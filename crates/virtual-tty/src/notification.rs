@@ -0,0 +1,7 @@
+/// A desktop notification requested by the program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    /// Empty for OSC 9, which only carries a message
+    pub title: String,
+    pub body: String,
+}
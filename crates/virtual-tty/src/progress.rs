@@ -0,0 +1,37 @@
+/// State of the progress indicator set with `OSC 9 ; 4 ; state ; percent`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProgressState {
+    /// `0`: no progress is shown
+    #[default]
+    Cleared,
+    /// `1`: the task runs normally
+    Normal,
+    /// `2`: the task failed
+    Error,
+    /// `3`: the task runs without a known completion
+    Indeterminate,
+    /// `4`: the task is paused
+    Paused,
+}
+
+impl ProgressState {
+    pub fn from_param(param: usize) -> Option<Self> {
+        match param {
+            0 => Some(ProgressState::Cleared),
+            1 => Some(ProgressState::Normal),
+            2 => Some(ProgressState::Error),
+            3 => Some(ProgressState::Indeterminate),
+            4 => Some(ProgressState::Paused),
+            _ => None,
+        }
+    }
+}
+
+/// A progress update, as shown in the taskbar or tab by modern terminals
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Progress {
+    pub state: ProgressState,
+    /// Completion between 0 and 100, if given with the update. Cleared and
+    /// indeterminate progress has none.
+    pub percent: Option<u8>,
+}
@@ -0,0 +1,180 @@
+use crate::buffer::Buffer;
+
+/// A position in the primary screen's history: history row (see
+/// `Buffer::scrolled_off`) and column
+pub type HistoryPosition = (usize, usize);
+
+/// Shell integration marks sent with OSC 133 (FinalTerm semantic prompts)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SemanticMark {
+    /// `A`: a prompt starts
+    PromptStart,
+    /// `B`: the prompt ends and the user types the command
+    CommandStart,
+    /// `C`: the command was entered and its output starts
+    OutputStart,
+    /// `D` with an optional exit status: the command finished
+    CommandFinished(Option<i32>),
+}
+
+/// A command run at a prompt, reconstructed from OSC 133 marks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SemanticCommand {
+    pub prompt: String,
+    /// The command line typed after the prompt
+    pub command: String,
+    pub output: String,
+    /// The exit status given with the `D` mark
+    pub exit_code: Option<i32>,
+    /// Whether the `D` mark was received (or the next prompt started)
+    pub finished: bool,
+}
+
+/// Number of parts in a zone: prompt, command and output
+const PARTS: usize = 3;
+
+/// One prompt and its command. The text of each part is captured when the
+/// next mark ends it, so that later edits of the screen (clearing it,
+/// scrolling a region, inserting lines) do not change it.
+#[derive(Debug, Clone)]
+struct Zone {
+    /// Text of the parts that ended, in order: prompt, command, output
+    parts: Vec<String>,
+    /// Where the part being written starts
+    start: HistoryPosition,
+    /// Text of the part being written that was erased by clearing the screen
+    /// or the scrollback
+    erased: String,
+    end: Option<HistoryPosition>,
+    exit_code: Option<i32>,
+}
+
+impl Zone {
+    /// Ends the first `part` parts (1 for the prompt, 2 up to the command):
+    /// the one being written ends at `position` and those skipped stay empty.
+    /// Parts that already ended are kept.
+    fn end_parts(&mut self, part: usize, buffer: &Buffer, position: HistoryPosition) {
+        if self.parts.len() < part {
+            let text = self.current_text(buffer, position);
+            self.parts.push(text);
+            self.parts.resize(part, String::new());
+            self.start = position;
+            self.erased.clear();
+        }
+    }
+
+    /// Keeps the text of the part being written up to `end`, which is about
+    /// to be erased, and continues the part at `restart`
+    fn erase(&mut self, buffer: &Buffer, end: HistoryPosition, restart: HistoryPosition) {
+        self.erased = self.current_text(buffer, end);
+        self.start = restart;
+    }
+
+    /// The text of the part being written, up to `position`
+    fn current_text(&self, buffer: &Buffer, position: HistoryPosition) -> String {
+        let text = buffer.history_text(self.start, position);
+        match (self.erased.is_empty(), text.is_empty()) {
+            (true, _) => text,
+            (false, true) => self.erased.clone(),
+            (false, false) => format!("{}\n{text}", self.erased),
+        }
+    }
+
+    fn finish(&mut self, buffer: &Buffer, position: HistoryPosition) {
+        self.end_parts(PARTS, buffer, position);
+        self.end = Some(position);
+    }
+}
+
+/// The prompt zones marked on the primary screen and its scrollback
+#[derive(Debug, Clone, Default)]
+pub struct SemanticZones {
+    zones: Vec<Zone>,
+}
+
+impl SemanticZones {
+    pub fn mark(&mut self, mark: SemanticMark, buffer: &Buffer, position: HistoryPosition) {
+        match (mark, self.open_zone()) {
+            (SemanticMark::PromptStart, current) => {
+                // A prompt without a `D` mark before it ends the previous command
+                if let Some(zone) = current {
+                    zone.finish(buffer, position);
+                }
+                self.zones.push(Zone {
+                    parts: Vec::new(),
+                    start: position,
+                    erased: String::new(),
+                    end: None,
+                    exit_code: None,
+                });
+            }
+            (SemanticMark::CommandStart, Some(zone)) => {
+                zone.end_parts(1, buffer, position);
+            }
+            (SemanticMark::OutputStart, Some(zone)) => {
+                zone.end_parts(2, buffer, position);
+            }
+            (SemanticMark::CommandFinished(exit_code), Some(zone)) => {
+                zone.finish(buffer, position);
+                zone.exit_code = exit_code;
+            }
+            // Marks outside of a prompt zone are ignored
+            (_, None) => {}
+        }
+    }
+
+    /// Keeps the text the open zone wrote up to `cursor` before the screen is
+    /// cleared. The output that follows starts again at `home`.
+    pub fn screen_cleared(
+        &mut self,
+        buffer: &Buffer,
+        cursor: HistoryPosition,
+        home: HistoryPosition,
+    ) {
+        if let Some(zone) = self.open_zone() {
+            zone.erase(buffer, cursor, home);
+        }
+    }
+
+    /// Keeps the text the open zone wrote in the scrollback of `buffer` before
+    /// it is cleared. Its text on the screen is still read from the screen.
+    pub fn scrollback_cleared(&mut self, buffer: &Buffer) {
+        let screen_start = (buffer.scrolled_off, 0);
+        if let Some(zone) = self.open_zone().filter(|zone| zone.start < screen_start) {
+            zone.erase(buffer, screen_start, screen_start);
+        }
+    }
+
+    fn open_zone(&mut self) -> Option<&mut Zone> {
+        self.zones.last_mut().filter(|zone| zone.end.is_none())
+    }
+
+    /// Forgets the zones that ended before `row`, once they scrolled out of
+    /// the history
+    pub fn drop_before(&mut self, row: usize) {
+        self.zones
+            .retain(|zone| zone.end.is_none_or(|(end_row, _)| end_row >= row));
+    }
+
+    /// Returns every zone. The part being written in a zone still open is
+    /// read from `buffer` and ends at `cursor`.
+    pub fn commands(&self, buffer: &Buffer, cursor: HistoryPosition) -> Vec<SemanticCommand> {
+        self.zones
+            .iter()
+            .map(|zone| {
+                let mut parts = zone.parts.clone();
+                if zone.end.is_none() {
+                    parts.push(zone.current_text(buffer, cursor));
+                }
+                let mut parts = parts.into_iter();
+                SemanticCommand {
+                    prompt: parts.next().unwrap_or_default(),
+                    command: parts.next().unwrap_or_default(),
+                    output: parts.next().unwrap_or_default(),
+                    exit_code: zone.exit_code,
+                    finished: zone.end.is_some(),
+                }
+            })
+            .collect()
+    }
+}
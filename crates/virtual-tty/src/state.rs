@@ -1,21 +1,169 @@
+use crate::ansi::{
+    parse_escape_sequence, AnsiCommand, CharsetSlot, ClearMode, ControlChar, GraphicsRendition,
+    Mode, StreamingParser, TabClearMode, Token,
+};
 use crate::buffer::Buffer;
-use crate::cursor::Cursor;
+use crate::cell::{Cell, CellAttributes, Hyperlink, HyperlinkSpan};
+use crate::charset::CharsetState;
+use crate::clipboard::Clipboard;
+use crate::cursor::{Cursor, SavedCursor};
+use crate::device::DeviceIdentity;
+use crate::notification::Notification;
+use crate::progress::Progress;
+use crate::semantic::{HistoryPosition, SemanticCommand, SemanticZones};
+use crate::tabs::TabStops;
+use crate::title::Titles;
+use crate::utf8::Utf8Decoder;
+use crate::width::{char_width, ZWJ};
+use std::sync::Arc;
+
+/// One of the two screens of the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Screen {
+    /// The normal screen, used by shells and line-oriented programs
+    Primary,
+    /// The screen full-screen applications (vim, less, htop...) switch to
+    Alternate,
+}
 
 /// Unified state structure that combines buffer and cursor data
 /// This replaces the previous dual-mutex approach with a single mutex
 pub struct TtyState {
+    /// The buffer of the currently active screen
     pub buffer: Buffer,
+    /// The buffer of the screen that is not currently displayed
+    pub inactive_buffer: Buffer,
+    pub active_screen: Screen,
     pub cursor: Cursor,
+    pub saved_cursor: Option<SavedCursor>,
+    /// First line of the scroll region set by DECSTBM
+    pub scroll_top: usize,
+    /// Last line (inclusive) of the scroll region set by DECSTBM
+    pub scroll_bottom: usize,
+    /// First column of the horizontal margins set by DECSLRM
+    pub scroll_left: usize,
+    /// Last column (inclusive) of the horizontal margins set by DECSLRM
+    pub scroll_right: usize,
+    /// DECOM: whether cursor addressing is relative to the margins
+    pub origin_mode: bool,
+    /// DECLRMM: whether DECSLRM can set left and right margins
+    pub left_right_margin_mode: bool,
+    /// Attributes applied to newly written characters, changed by SGR sequences
+    pub pen: CellAttributes,
+    pub charsets: CharsetState,
+    pub tab_stops: TabStops,
+    /// DECAWM: whether printing past the last column wraps to the next line
+    pub autowrap: bool,
+    /// IRM: whether printed characters shift the rest of the line right
+    pub insert_mode: bool,
+    /// LNM: whether LF, VT and FF also perform a carriage return
+    pub newline_mode: bool,
+    /// OSC 8 hyperlink given to newly written characters
+    pub hyperlink: Option<Arc<Hyperlink>>,
+    /// Window title and icon name with their stack
+    pub titles: Titles,
+    /// Prompts and commands marked with OSC 133 on the primary screen
+    pub semantic_zones: SemanticZones,
+    /// Content copied with OSC 52
+    pub clipboard: Clipboard,
+    /// Path reported by the shell with OSC 7
+    pub working_directory: Option<String>,
+    /// Desktop notifications sent with OSC 9 or OSC 777, oldest first
+    pub notifications: Vec<Notification>,
+    /// Progress indicator set with OSC 9;4
+    pub progress: Progress,
+    /// Every progress update received, oldest first
+    pub progress_history: Vec<Progress>,
+    /// What the terminal reports to DA1, DA2 and XTVERSION queries
+    pub identity: DeviceIdentity,
+    /// Replies to queries, waiting to be read by the program on the other end
+    pub responses: Vec<String>,
+    /// Keeps multibyte characters split across writes of any stream
+    pub decoder: Utf8Decoder,
+    /// Keeps escape sequences split across writes of any stream
+    pub parser: StreamingParser,
+    /// Cell written by the last printed character if no other token was
+    /// processed since. Following zero-width characters are combined with it.
+    pub last_printed: Option<(usize, usize)>,
 }
 
 impl TtyState {
-    pub fn new(width: usize, height: usize) -> Self {
+    pub fn new(width: usize, height: usize, scrollback_limit: usize) -> Self {
         Self {
-            buffer: Buffer::new(width, height),
+            // Only the primary screen keeps a scrollback, like xterm
+            buffer: Buffer::with_scrollback(width, height, scrollback_limit),
+            inactive_buffer: Buffer::new(width, height),
+            active_screen: Screen::Primary,
             cursor: Cursor::new(),
+            saved_cursor: None,
+            scroll_top: 0,
+            scroll_bottom: height.saturating_sub(1),
+            scroll_left: 0,
+            scroll_right: width.saturating_sub(1),
+            origin_mode: false,
+            left_right_margin_mode: false,
+            pen: CellAttributes::default(),
+            charsets: CharsetState::default(),
+            tab_stops: TabStops::new(width),
+            autowrap: true,
+            insert_mode: false,
+            newline_mode: false,
+            hyperlink: None,
+            titles: Titles::default(),
+            semantic_zones: SemanticZones::default(),
+            clipboard: Clipboard::default(),
+            working_directory: None,
+            notifications: Vec::new(),
+            progress: Progress::default(),
+            progress_history: Vec::new(),
+            identity: DeviceIdentity::default(),
+            responses: Vec::new(),
+            decoder: Utf8Decoder::new(),
+            parser: StreamingParser::new(),
+            last_printed: None,
         }
     }
 
+    /// Returns the terminal to its power-on state. The scrollback limit, the
+    /// device identity, what belongs to the window or the session (titles,
+    /// clipboard, working directory, notifications, progress) and unread
+    /// responses are kept.
+    pub fn reset(&mut self) {
+        let (width, height) = (self.buffer.width, self.buffer.height);
+        let scrollback_limit = self.screen_buffer(Screen::Primary).scrollback_limit;
+        let old = std::mem::replace(self, Self::new(width, height, scrollback_limit));
+        self.titles = old.titles;
+        self.clipboard = old.clipboard;
+        self.working_directory = old.working_directory;
+        self.notifications = old.notifications;
+        self.progress = old.progress;
+        self.progress_history = old.progress_history;
+        self.identity = old.identity;
+        self.responses = old.responses;
+        // The decoder and parser may hold the beginning of the output
+        // following the reset
+        self.decoder = old.decoder;
+        self.parser = old.parser;
+    }
+
+    /// Changes the screen size, keeping the top-left part of both screens.
+    /// The margins and tab stops are reset to their defaults.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        self.buffer = Buffer::resize_from(&self.buffer, width, height);
+        self.inactive_buffer = Buffer::resize_from(&self.inactive_buffer, width, height);
+        self.cursor
+            .set_position(self.cursor.row, self.cursor.col, height, width);
+        self.scroll_top = 0;
+        self.scroll_bottom = height.saturating_sub(1);
+        self.scroll_left = 0;
+        self.scroll_right = width.saturating_sub(1);
+        self.tab_stops = TabStops::new(width);
+    }
+
+    pub fn get_tab_stops(&self) -> Vec<usize> {
+        self.tab_stops.columns()
+    }
+
     pub fn clear(&mut self, width: usize, height: usize) {
         self.buffer.clear();
         self.cursor.set_position(0, 0, height, width);
@@ -25,7 +173,773 @@ impl TtyState {
         self.buffer.get_snapshot()
     }
 
+    pub fn get_styled_snapshot(&self) -> String {
+        self.buffer.get_styled_snapshot()
+    }
+
+    pub fn get_screen_snapshot(&self, screen: Screen) -> String {
+        self.screen_buffer(screen).get_snapshot()
+    }
+
+    pub fn get_scrollback_snapshot(&self) -> String {
+        self.screen_buffer(Screen::Primary)
+            .get_scrollback_snapshot()
+    }
+
+    pub fn get_history_snapshot(&self) -> String {
+        self.screen_buffer(Screen::Primary).get_history_snapshot()
+    }
+
+    pub fn get_scrollback_len(&self) -> usize {
+        self.screen_buffer(Screen::Primary).scrollback.len()
+    }
+
+    pub fn set_scrollback_limit(&mut self, limit: usize) {
+        self.screen_buffer_mut(Screen::Primary)
+            .set_scrollback_limit(limit);
+    }
+
+    pub fn clear_scrollback(&mut self) {
+        // `screen_buffer` would borrow all of `self`
+        let primary = if self.active_screen == Screen::Primary {
+            &self.buffer
+        } else {
+            &self.inactive_buffer
+        };
+        self.semantic_zones.scrollback_cleared(primary);
+        self.screen_buffer_mut(Screen::Primary).clear_scrollback();
+    }
+
+    fn screen_buffer(&self, screen: Screen) -> &Buffer {
+        if screen == self.active_screen {
+            &self.buffer
+        } else {
+            &self.inactive_buffer
+        }
+    }
+
+    fn screen_buffer_mut(&mut self, screen: Screen) -> &mut Buffer {
+        if screen == self.active_screen {
+            &mut self.buffer
+        } else {
+            &mut self.inactive_buffer
+        }
+    }
+
     pub fn get_cursor_position(&self) -> (usize, usize) {
         self.cursor.get_position()
     }
+
+    /// Returns the commands marked with OSC 133, oldest first
+    pub fn get_commands(&self) -> Vec<SemanticCommand> {
+        let primary = self.screen_buffer(Screen::Primary);
+        // While the alternate screen is shown, open zones end with the primary screen
+        let end = if self.active_screen == Screen::Primary {
+            self.cursor_history_position()
+        } else {
+            (primary.scrolled_off + primary.height, 0)
+        };
+        self.semantic_zones.commands(primary, end)
+    }
+
+    /// Returns the cursor position as history row and column. A pending wrap
+    /// places it after the last column.
+    fn cursor_history_position(&self) -> HistoryPosition {
+        let col = self.cursor.col + usize::from(self.cursor.pending_wrap);
+        (self.buffer.scrolled_off + self.cursor.row, col)
+    }
+
+    pub fn get_hyperlinks(&self) -> Vec<HyperlinkSpan> {
+        self.buffer.hyperlink_spans()
+    }
+
+    pub fn get_cell(&self, row: usize, col: usize) -> Option<Cell> {
+        self.buffer.get_cell(row, col).cloned()
+    }
+
+    /// Writes raw output bytes. An incomplete UTF-8 sequence at the end is
+    /// kept until the next write, invalid bytes are shown as U+FFFD.
+    pub fn write_bytes(&mut self, data: &[u8]) {
+        let data = self.decoder.decode(data);
+        self.write(&data);
+    }
+
+    /// Parses `data` and applies it to the terminal. An escape sequence split
+    /// across writes is applied once its end arrives.
+    pub fn write(&mut self, data: &str) {
+        // Use the new tokenized parser
+        match self.parser.feed(data) {
+            Ok(tokens) => {
+                for token in tokens {
+                    self.process_token(token);
+                }
+            }
+            Err(_) => {
+                // Fallback to legacy parsing for compatibility
+                self.write_legacy(data);
+            }
+        }
+    }
+
+    fn write_legacy(&mut self, data: &str) {
+        let mut chars = data.chars();
+        while let Some(ch) = chars.next() {
+            if ch == '\x1b' {
+                // Start of escape sequence
+                if chars.next() == Some('[') {
+                    if let Some(command) = parse_escape_sequence(&mut chars) {
+                        self.execute_ansi_command(&command);
+                    }
+                }
+            } else if ch == '\r' {
+                // Carriage return
+                self.carriage_return();
+            } else if ch == '\n' {
+                // Newline
+                self.line_feed();
+            } else if ch == '\x08' {
+                // Backspace
+                self.cursor.backspace();
+            } else {
+                // Regular character
+                self.print_char(ch);
+            }
+        }
+    }
+
+    pub fn process_token(&mut self, token: Token) {
+        match token {
+            Token::Text(text) => {
+                for ch in text.chars() {
+                    self.print_char(ch);
+                }
+                return;
+            }
+            Token::Command(command) => {
+                // Validate command before executing
+                if command.validate().is_ok() {
+                    self.execute_ansi_command(&command);
+                }
+                // If validation fails, silently ignore the command
+            }
+            Token::ControlChar(ctrl_char) => {
+                match ctrl_char {
+                    ControlChar::LineFeed => {
+                        self.line_feed();
+                    }
+                    ControlChar::CarriageReturn => {
+                        self.carriage_return();
+                    }
+                    ControlChar::Backspace => {
+                        self.cursor.backspace();
+                    }
+                    ControlChar::Tab => {
+                        // Move to the next tab stop, or the last column if there is none
+                        let next = self.tab_stops.next(self.cursor.col, 1);
+                        self.cursor.set_col(next.min(self.right_limit()));
+                    }
+                    ControlChar::Bell => {
+                        // Bell character - typically ignored in terminal emulation
+                    }
+                    ControlChar::VerticalTab => {
+                        // Vertical tab - move to next line
+                        self.line_feed();
+                    }
+                    ControlChar::ShiftOut => {
+                        self.charsets.shifted_out = true;
+                    }
+                    ControlChar::ShiftIn => {
+                        self.charsets.shifted_out = false;
+                    }
+                    ControlChar::FormFeed => {
+                        // Form feed - clear screen and move to top
+                        let (width, height) = (self.buffer.width, self.buffer.height);
+                        self.buffer.clear();
+                        self.cursor.set_position(0, 0, height, width);
+                    }
+                }
+            }
+            Token::Invalid(_) => {
+                // Ignore invalid tokens for now
+            }
+        }
+        self.last_printed = None;
+    }
+
+    pub fn execute_ansi_command(&mut self, command: &AnsiCommand) {
+        let (width, height) = (self.buffer.width, self.buffer.height);
+        match command {
+            AnsiCommand::CursorUp(n) => {
+                self.cursor.move_up(*n, self.top_limit());
+            }
+            AnsiCommand::CursorDown(n) => {
+                self.cursor.move_down(*n, self.bottom_limit() + 1);
+            }
+            AnsiCommand::CursorForward(n) => {
+                self.cursor.move_forward(*n, self.right_limit() + 1);
+            }
+            AnsiCommand::CursorBack(n) => {
+                self.cursor.move_back(*n, self.left_limit());
+            }
+            AnsiCommand::CursorPosition { row, col } => {
+                let (row, col) = (self.origin_row(*row), self.origin_col(*col));
+                self.cursor.set_position(row, col, height, width);
+            }
+            AnsiCommand::CursorColumn(col) => {
+                let col = self.origin_col(*col);
+                self.cursor
+                    .set_position(self.cursor.row, col, height, width);
+            }
+            AnsiCommand::CursorRow(row) => {
+                let row = self.origin_row(*row);
+                self.cursor
+                    .set_position(row, self.cursor.col, height, width);
+            }
+            AnsiCommand::CursorNextLine(n) => {
+                self.cursor.move_down(*n, self.bottom_limit() + 1);
+                self.carriage_return();
+            }
+            AnsiCommand::CursorPreviousLine(n) => {
+                self.cursor.move_up(*n, self.top_limit());
+                self.carriage_return();
+            }
+            AnsiCommand::ClearScreen(clear_mode) => match clear_mode {
+                ClearMode::Entire => {
+                    if self.active_screen == Screen::Primary {
+                        // Output after the clear starts again at the top
+                        let home = (self.buffer.scrolled_off, 0);
+                        let cursor = self.cursor_history_position();
+                        self.semantic_zones
+                            .screen_cleared(&self.buffer, cursor, home);
+                    }
+                    self.buffer.clear();
+                    self.cursor.set_position(0, 0, height, width);
+                }
+                ClearMode::ToBeginning => {
+                    let cursor_row = self.cursor.row;
+                    let cursor_col = self.cursor.col;
+                    self.buffer
+                        .clear_from_beginning_to_cursor(cursor_row, cursor_col);
+                }
+                ClearMode::ToEnd => {
+                    let cursor_row = self.cursor.row;
+                    let cursor_col = self.cursor.col;
+                    self.buffer.clear_from_cursor_to_end(cursor_row, cursor_col);
+                }
+            },
+            AnsiCommand::ClearLine(clear_mode) => match clear_mode {
+                ClearMode::Entire => {
+                    let cursor_row = self.cursor.row;
+                    self.buffer.clear_entire_line(cursor_row);
+                }
+                ClearMode::ToBeginning => {
+                    let cursor_row = self.cursor.row;
+                    let cursor_col = self.cursor.col;
+                    self.buffer
+                        .clear_line_from_beginning_to_cursor(cursor_row, cursor_col);
+                }
+                ClearMode::ToEnd => {
+                    let cursor_row = self.cursor.row;
+                    let cursor_col = self.cursor.col;
+                    self.buffer
+                        .clear_line_from_cursor_to_end(cursor_row, cursor_col);
+                }
+            },
+            AnsiCommand::InsertChars(n) => {
+                let right = self.right_limit();
+                self.buffer
+                    .insert_chars(self.cursor.row, self.cursor.col, right, *n);
+            }
+            AnsiCommand::DeleteChars(n) => {
+                let right = self.right_limit();
+                self.buffer
+                    .delete_chars(self.cursor.row, self.cursor.col, right, *n);
+            }
+            AnsiCommand::EraseChars(n) => {
+                self.buffer
+                    .erase_chars(self.cursor.row, self.cursor.col, *n);
+            }
+            AnsiCommand::InsertLines(n) => {
+                if self.cursor_in_scroll_region() && self.cursor_in_horizontal_margins() {
+                    self.buffer.insert_lines(
+                        self.cursor.row,
+                        self.scroll_bottom,
+                        self.scroll_left,
+                        self.scroll_right,
+                        *n,
+                    );
+                    self.carriage_return();
+                }
+            }
+            AnsiCommand::DeleteLines(n) => {
+                if self.cursor_in_scroll_region() && self.cursor_in_horizontal_margins() {
+                    self.buffer.delete_lines(
+                        self.cursor.row,
+                        self.scroll_bottom,
+                        self.scroll_left,
+                        self.scroll_right,
+                        *n,
+                    );
+                    self.carriage_return();
+                }
+            }
+            AnsiCommand::ScrollUp(n) => {
+                self.scroll_up(*n);
+            }
+            AnsiCommand::ScrollDown(n) => {
+                self.scroll_down(*n);
+            }
+            AnsiCommand::Index => {
+                self.index();
+            }
+            AnsiCommand::ReverseIndex => {
+                self.reverse_index();
+            }
+            AnsiCommand::NextLine => {
+                self.next_line();
+            }
+            AnsiCommand::ClearScrollback => {
+                self.clear_scrollback();
+            }
+            AnsiCommand::SetGraphicsRendition(renditions) => {
+                for rendition in renditions {
+                    self.apply_graphics_rendition(rendition);
+                }
+            }
+            AnsiCommand::SetScrollRegion { top, bottom } => {
+                self.set_scroll_region(*top, *bottom);
+            }
+            AnsiCommand::SetLeftRightMargins { left, right } => {
+                if self.left_right_margin_mode {
+                    self.set_left_right_margins(*left, *right);
+                } else {
+                    self.save_cursor();
+                }
+            }
+            AnsiCommand::SaveCursor => {
+                self.save_cursor();
+            }
+            AnsiCommand::RestoreCursor => {
+                self.restore_cursor();
+            }
+            AnsiCommand::SetTabStop => {
+                self.tab_stops.set(self.cursor.col);
+            }
+            AnsiCommand::ClearTabStop(clear_mode) => match clear_mode {
+                TabClearMode::Current => self.tab_stops.clear(self.cursor.col),
+                TabClearMode::All => self.tab_stops.clear_all(),
+            },
+            AnsiCommand::ForwardTab(n) => {
+                let next = self.tab_stops.next(self.cursor.col, *n);
+                self.cursor.set_col(next.min(self.right_limit()));
+            }
+            AnsiCommand::BackTab(n) => {
+                self.cursor
+                    .set_col(self.tab_stops.previous(self.cursor.col, *n));
+            }
+            AnsiCommand::Reset => {
+                self.reset();
+            }
+            AnsiCommand::DesignateCharset { slot, charset } => match slot {
+                CharsetSlot::G0 => self.charsets.g0 = *charset,
+                CharsetSlot::G1 => self.charsets.g1 = *charset,
+            },
+            AnsiCommand::SetTitle { target, title } => {
+                self.titles.set(*target, title);
+            }
+            AnsiCommand::SetHyperlink(hyperlink) => {
+                self.hyperlink = hyperlink.clone().map(Arc::new);
+            }
+            AnsiCommand::SemanticPrompt(mark) => {
+                // Shells only run on the primary screen
+                if self.active_screen == Screen::Primary {
+                    let buffer = &self.buffer;
+                    self.semantic_zones
+                        .drop_before(buffer.scrolled_off - buffer.scrollback.len());
+                    let position = self.cursor_history_position();
+                    self.semantic_zones.mark(*mark, &self.buffer, position);
+                }
+            }
+            AnsiCommand::SetWorkingDirectory(path) => {
+                self.working_directory = Some(path.clone());
+            }
+            AnsiCommand::Notify(notification) => {
+                self.notifications.push(notification.clone());
+            }
+            AnsiCommand::SetProgress(progress) => {
+                self.progress = *progress;
+                self.progress_history.push(*progress);
+            }
+            AnsiCommand::SetClipboard { selection, content } => {
+                self.clipboard.write(selection, content.clone());
+            }
+            AnsiCommand::QueryClipboard { selection } => {
+                let response = self.clipboard.query_response(selection);
+                self.responses.push(response);
+            }
+            AnsiCommand::StatusReport => {
+                // Always report that the terminal is working
+                self.responses.push("\x1b[0n".to_string());
+            }
+            AnsiCommand::CursorPositionReport => {
+                let (mut row, mut col) = (self.cursor.row, self.cursor.col);
+                // In origin mode the position is relative to the margins
+                if self.origin_mode {
+                    row = row.saturating_sub(self.scroll_top);
+                    col = col.saturating_sub(self.scroll_left);
+                }
+                self.responses
+                    .push(format!("\x1b[{};{}R", row + 1, col + 1));
+            }
+            AnsiCommand::PrimaryDeviceAttributes => {
+                let response = self.identity.primary_attributes_response();
+                self.responses.push(response);
+            }
+            AnsiCommand::SecondaryDeviceAttributes => {
+                let response = self.identity.secondary_attributes_response();
+                self.responses.push(response);
+            }
+            AnsiCommand::TerminalVersion => {
+                let response = self.identity.version_response();
+                self.responses.push(response);
+            }
+            AnsiCommand::PushTitle(target) => {
+                self.titles.push(*target);
+            }
+            AnsiCommand::PopTitle(target) => {
+                self.titles.pop(*target);
+            }
+            AnsiCommand::SetMode(modes) => {
+                for mode in modes {
+                    self.set_mode(*mode, true);
+                }
+            }
+            AnsiCommand::ResetMode(modes) => {
+                for mode in modes {
+                    self.set_mode(*mode, false);
+                }
+            }
+        }
+    }
+
+    fn set_mode(&mut self, mode: Mode, enabled: bool) {
+        match mode {
+            Mode::Insert => self.insert_mode = enabled,
+            Mode::NewLine => self.newline_mode = enabled,
+            Mode::Origin => {
+                self.origin_mode = enabled;
+                self.home_cursor();
+            }
+            Mode::LeftRightMargins => {
+                self.left_right_margin_mode = enabled;
+                if !enabled {
+                    self.scroll_left = 0;
+                    self.scroll_right = self.buffer.width.saturating_sub(1);
+                }
+            }
+            Mode::AutoWrap => {
+                self.autowrap = enabled;
+                if !enabled {
+                    self.cursor.pending_wrap = false;
+                }
+            }
+            Mode::AlternateScreen => {
+                self.switch_screen(if enabled {
+                    Screen::Alternate
+                } else {
+                    Screen::Primary
+                });
+            }
+            Mode::AlternateScreenClear => {
+                if enabled {
+                    self.switch_screen(Screen::Alternate);
+                } else {
+                    if self.active_screen == Screen::Alternate {
+                        self.buffer.clear();
+                    }
+                    self.switch_screen(Screen::Primary);
+                }
+            }
+            Mode::AlternateScreenSaveCursor => {
+                if enabled {
+                    self.save_cursor();
+                    self.switch_screen(Screen::Alternate);
+                    self.buffer.clear();
+                } else {
+                    self.switch_screen(Screen::Primary);
+                    self.restore_cursor();
+                }
+            }
+        }
+    }
+
+    fn switch_screen(&mut self, screen: Screen) {
+        if self.active_screen != screen {
+            std::mem::swap(&mut self.buffer, &mut self.inactive_buffer);
+            self.active_screen = screen;
+        }
+    }
+
+    fn save_cursor(&mut self) {
+        self.saved_cursor = Some(SavedCursor {
+            row: self.cursor.row,
+            col: self.cursor.col,
+            pending_wrap: self.cursor.pending_wrap,
+            origin_mode: self.origin_mode,
+            pen: self.pen,
+            charsets: self.charsets,
+        });
+    }
+
+    fn restore_cursor(&mut self) {
+        // Without a saved state the cursor returns home with default attributes
+        let saved = self.saved_cursor.clone().unwrap_or_default();
+        let (width, height) = (self.buffer.width, self.buffer.height);
+        self.cursor
+            .set_position(saved.row, saved.col, height, width);
+        self.cursor.pending_wrap = saved.pending_wrap && self.autowrap;
+        self.origin_mode = saved.origin_mode;
+        self.pen = saved.pen;
+        self.charsets = saved.charsets;
+    }
+
+    fn apply_graphics_rendition(&mut self, rendition: &GraphicsRendition) {
+        let pen = &mut self.pen;
+        match rendition {
+            GraphicsRendition::Reset => *pen = CellAttributes::default(),
+            GraphicsRendition::Bold => pen.bold = true,
+            GraphicsRendition::Dim => pen.dim = true,
+            GraphicsRendition::Italic => pen.italic = true,
+            GraphicsRendition::Underline => pen.underline = true,
+            GraphicsRendition::Blink => pen.blink = true,
+            GraphicsRendition::Reverse => pen.reverse = true,
+            GraphicsRendition::Hidden => pen.hidden = true,
+            GraphicsRendition::Strikethrough => pen.strikethrough = true,
+            GraphicsRendition::NormalIntensity => {
+                pen.bold = false;
+                pen.dim = false;
+            }
+            GraphicsRendition::NoItalic => pen.italic = false,
+            GraphicsRendition::NoUnderline => pen.underline = false,
+            GraphicsRendition::NoBlink => pen.blink = false,
+            GraphicsRendition::NoReverse => pen.reverse = false,
+            GraphicsRendition::NoHidden => pen.hidden = false,
+            GraphicsRendition::NoStrikethrough => pen.strikethrough = false,
+            GraphicsRendition::Foreground(color) => pen.foreground = *color,
+            GraphicsRendition::Background(color) => pen.background = *color,
+        }
+    }
+
+    fn print_char(&mut self, ch: char) {
+        let ch = self.charsets.translate(ch);
+        let (width, height) = (self.buffer.width, self.buffer.height);
+        let char_width = char_width(ch);
+        if char_width == 0 || self.last_printed_ends_with_zwj() {
+            // Zero-width characters extend the grapheme cluster of the previous cell
+            if let Some((row, col)) = self.last_printed {
+                self.buffer.append_to_cell(row, col, ch);
+            }
+            return;
+        }
+        let is_wide = char_width == 2;
+        if is_wide && width < 2 {
+            return; // A wide character can never fit
+        }
+        if self.cursor.pending_wrap {
+            self.next_line();
+        }
+        // A wide character that does not fit before the right margin wraps as a whole
+        let right = self.right_limit();
+        if is_wide && self.cursor.col == right {
+            if self.autowrap {
+                self.next_line();
+            } else {
+                self.cursor.move_back(1, 0);
+            }
+        }
+        let right = self.right_limit();
+        let cursor_row = self.cursor.row;
+        let cursor_col = self.cursor.col;
+        if cursor_row < height && cursor_col < width {
+            if self.insert_mode {
+                self.buffer
+                    .insert_chars(cursor_row, cursor_col, right, char_width);
+            }
+            let link = &self.hyperlink;
+            if is_wide {
+                let cell = Cell::wide(ch, self.pen).with_hyperlink(link.clone());
+                let continuation = Cell::continuation(self.pen).with_hyperlink(link.clone());
+                self.buffer.set_cell(cursor_row, cursor_col, cell);
+                self.buffer
+                    .set_cell(cursor_row, cursor_col + 1, continuation);
+            } else {
+                let cell = Cell::new(ch, self.pen).with_hyperlink(link.clone());
+                self.buffer.set_cell(cursor_row, cursor_col, cell);
+            }
+            self.last_printed = Some((cursor_row, cursor_col));
+            self.cursor.advance(char_width, right + 1, self.autowrap);
+        }
+    }
+
+    fn last_printed_ends_with_zwj(&self) -> bool {
+        self.last_printed
+            .and_then(|(row, col)| self.buffer.get_cell(row, col))
+            .is_some_and(|cell| cell.combining.last() == Some(&ZWJ))
+    }
+
+    /// CR: moves to the left margin, or the first column if the cursor is
+    /// left of it
+    fn carriage_return(&mut self) {
+        self.cursor.set_col(self.left_limit());
+    }
+
+    /// LF, VT and FF: moves down one line, also returning to the first column
+    /// in newline mode
+    fn line_feed(&mut self) {
+        if self.newline_mode {
+            self.carriage_return();
+        }
+        self.index();
+    }
+
+    /// Moves to the first column of the next line, scrolling at the bottom margin
+    fn next_line(&mut self) {
+        self.carriage_return();
+        self.index();
+    }
+
+    /// IND: moves the cursor down, scrolling the region at its bottom margin
+    fn index(&mut self) {
+        if self.cursor.index(self.scroll_bottom, self.buffer.height)
+            && self.cursor_in_horizontal_margins()
+        {
+            self.scroll_up(1);
+        }
+    }
+
+    /// RI: moves the cursor up, scrolling the region at its top margin
+    fn reverse_index(&mut self) {
+        if self.cursor.reverse_index(self.scroll_top) && self.cursor_in_horizontal_margins() {
+            self.scroll_down(1);
+        }
+    }
+
+    /// Scrolls the content of the scroll region up by `n` lines
+    fn scroll_up(&mut self, n: usize) {
+        self.buffer.scroll_up(
+            self.scroll_top,
+            self.scroll_bottom,
+            self.scroll_left,
+            self.scroll_right,
+            n,
+        );
+    }
+
+    /// Scrolls the content of the scroll region down by `n` lines
+    fn scroll_down(&mut self, n: usize) {
+        self.buffer.scroll_down(
+            self.scroll_top,
+            self.scroll_bottom,
+            self.scroll_left,
+            self.scroll_right,
+            n,
+        );
+    }
+
+    fn cursor_in_scroll_region(&self) -> bool {
+        (self.scroll_top..=self.scroll_bottom).contains(&self.cursor.row)
+    }
+
+    fn cursor_in_horizontal_margins(&self) -> bool {
+        (self.scroll_left..=self.scroll_right).contains(&self.cursor.col)
+    }
+
+    // Relative movements stop at a margin when the cursor starts inside it,
+    // and at the edge of the screen otherwise
+
+    fn top_limit(&self) -> usize {
+        if self.cursor.row >= self.scroll_top {
+            self.scroll_top
+        } else {
+            0
+        }
+    }
+
+    fn bottom_limit(&self) -> usize {
+        if self.cursor.row <= self.scroll_bottom {
+            self.scroll_bottom
+        } else {
+            self.buffer.height.saturating_sub(1)
+        }
+    }
+
+    fn left_limit(&self) -> usize {
+        if self.cursor.col >= self.scroll_left {
+            self.scroll_left
+        } else {
+            0
+        }
+    }
+
+    fn right_limit(&self) -> usize {
+        if self.cursor.col <= self.scroll_right {
+            self.scroll_right
+        } else {
+            self.buffer.width.saturating_sub(1)
+        }
+    }
+
+    /// Converts a line given to CUP or VPA to a screen line: in origin mode
+    /// lines are counted from the top margin and cannot leave the region
+    fn origin_row(&self, row: usize) -> usize {
+        if self.origin_mode {
+            self.scroll_top.saturating_add(row).min(self.scroll_bottom)
+        } else {
+            row
+        }
+    }
+
+    /// Converts a column given to CUP or CHA to a screen column: in origin
+    /// mode columns are counted from the left margin and cannot leave it
+    fn origin_col(&self, col: usize) -> usize {
+        if self.origin_mode {
+            self.scroll_left.saturating_add(col).min(self.scroll_right)
+        } else {
+            col
+        }
+    }
+
+    /// Moves the cursor to the top-left corner of the screen, or of the margins
+    /// in origin mode
+    fn home_cursor(&mut self) {
+        let (width, height) = (self.buffer.width, self.buffer.height);
+        let (row, col) = (self.origin_row(0), self.origin_col(0));
+        self.cursor.set_position(row, col, height, width);
+    }
+
+    fn set_scroll_region(&mut self, top: usize, bottom: Option<usize>) {
+        let height = self.buffer.height;
+        let last_line = height.saturating_sub(1);
+        let bottom = bottom.unwrap_or(last_line).min(last_line);
+        // The region must be at least two lines high, otherwise the sequence is ignored
+        if top >= bottom {
+            return;
+        }
+        self.scroll_top = top;
+        self.scroll_bottom = bottom;
+        self.home_cursor();
+    }
+
+    fn set_left_right_margins(&mut self, left: usize, right: Option<usize>) {
+        let width = self.buffer.width;
+        let last_column = width.saturating_sub(1);
+        let right = right.unwrap_or(last_column).min(last_column);
+        // Like DECSTBM, the margins must be at least two columns apart
+        if left >= right {
+            return;
+        }
+        self.scroll_left = left;
+        self.scroll_right = right;
+        self.home_cursor();
+    }
 }
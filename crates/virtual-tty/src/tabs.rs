@@ -0,0 +1,62 @@
+/// Default distance between tab stops
+const DEFAULT_TAB_WIDTH: usize = 8;
+
+/// Horizontal tab stops, set with HTS (`ESC H`) and cleared with TBC (`CSI g`)
+#[derive(Debug, Clone)]
+pub struct TabStops {
+    stops: Vec<bool>,
+}
+
+impl TabStops {
+    /// Creates the default table with a stop every 8 columns
+    pub fn new(width: usize) -> Self {
+        Self {
+            stops: (0..width)
+                .map(|col| col > 0 && col % DEFAULT_TAB_WIDTH == 0)
+                .collect(),
+        }
+    }
+
+    pub fn set(&mut self, col: usize) {
+        if let Some(stop) = self.stops.get_mut(col) {
+            *stop = true;
+        }
+    }
+
+    pub fn clear(&mut self, col: usize) {
+        if let Some(stop) = self.stops.get_mut(col) {
+            *stop = false;
+        }
+    }
+
+    pub fn clear_all(&mut self) {
+        self.stops.fill(false);
+    }
+
+    /// Returns the column of the `n`th stop after `col`, or the last column
+    /// if there are not enough stops
+    pub fn next(&self, col: usize, n: usize) -> usize {
+        let last = self.stops.len().saturating_sub(1);
+        (col + 1..self.stops.len())
+            .filter(|&col| self.stops[col])
+            .nth(n.saturating_sub(1))
+            .unwrap_or(last)
+    }
+
+    /// Returns the column of the `n`th stop before `col`, or the first column
+    /// if there are not enough stops
+    pub fn previous(&self, col: usize, n: usize) -> usize {
+        (0..col.min(self.stops.len()))
+            .rev()
+            .filter(|&col| self.stops[col])
+            .nth(n.saturating_sub(1))
+            .unwrap_or(0)
+    }
+
+    /// Returns the columns that have a tab stop, in ascending order
+    pub fn columns(&self) -> Vec<usize> {
+        (0..self.stops.len())
+            .filter(|&col| self.stops[col])
+            .collect()
+    }
+}
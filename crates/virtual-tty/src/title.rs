@@ -0,0 +1,74 @@
+/// Most titles kept on the stack, like xterm. Pushing more drops the oldest.
+const TITLE_STACK_LIMIT: usize = 10;
+
+/// Which of the window title and the icon name a title sequence applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TitleTarget {
+    /// `OSC 0` / `CSI 22;0 t` / `CSI 23;0 t`
+    IconAndWindow,
+    /// `OSC 1` / `CSI 22;1 t` / `CSI 23;1 t`
+    IconName,
+    /// `OSC 2` / `CSI 22;2 t` / `CSI 23;2 t`
+    Window,
+}
+
+impl TitleTarget {
+    fn includes_window(self) -> bool {
+        self != TitleTarget::IconName
+    }
+
+    fn includes_icon(self) -> bool {
+        self != TitleTarget::Window
+    }
+}
+
+/// An entry of the title stack, holding the titles saved by `CSI 22 t`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedTitle {
+    pub title: Option<String>,
+    pub icon_name: Option<String>,
+}
+
+/// Window title and icon name set by OSC 0/1/2, with the stack used by
+/// `CSI 22 t` (push) and `CSI 23 t` (pop)
+#[derive(Debug, Clone, Default)]
+pub struct Titles {
+    pub title: String,
+    pub icon_name: String,
+    pub stack: Vec<SavedTitle>,
+}
+
+impl Titles {
+    pub fn set(&mut self, target: TitleTarget, text: &str) {
+        if target.includes_window() {
+            self.title = text.to_string();
+        }
+        if target.includes_icon() {
+            self.icon_name = text.to_string();
+        }
+    }
+
+    pub fn push(&mut self, target: TitleTarget) {
+        if self.stack.len() == TITLE_STACK_LIMIT {
+            self.stack.remove(0);
+        }
+        self.stack.push(SavedTitle {
+            title: target.includes_window().then(|| self.title.clone()),
+            icon_name: target.includes_icon().then(|| self.icon_name.clone()),
+        });
+    }
+
+    /// Restores the requested titles from the top of the stack. Titles the
+    /// entry does not hold are left unchanged.
+    pub fn pop(&mut self, target: TitleTarget) {
+        let Some(saved) = self.stack.pop() else {
+            return;
+        };
+        if let Some(title) = saved.title.filter(|_| target.includes_window()) {
+            self.title = title;
+        }
+        if let Some(icon_name) = saved.icon_name.filter(|_| target.includes_icon()) {
+            self.icon_name = icon_name;
+        }
+    }
+}
@@ -0,0 +1,90 @@
+/// Incremental UTF-8 decoder for output that arrives in arbitrary chunks.
+///
+/// A character split across writes is kept until its remaining bytes arrive.
+/// Only bytes that can never become valid UTF-8 are replaced with U+FFFD.
+#[derive(Debug, Default)]
+pub struct Utf8Decoder {
+    pending: Vec<u8>,
+}
+
+impl Utf8Decoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decodes `input` together with the bytes left over by the previous call
+    pub fn decode(&mut self, input: &[u8]) -> String {
+        let data = if self.pending.is_empty() {
+            input.to_vec()
+        } else {
+            let mut data = std::mem::take(&mut self.pending);
+            data.extend_from_slice(input);
+            data
+        };
+
+        let mut output = String::with_capacity(data.len());
+        let mut rest = &data[..];
+        loop {
+            match std::str::from_utf8(rest) {
+                Ok(valid) => {
+                    output.push_str(valid);
+                    break;
+                }
+                Err(error) => {
+                    let (valid, invalid) = rest.split_at(error.valid_up_to());
+                    output.push_str(std::str::from_utf8(valid).unwrap());
+                    match error.error_len() {
+                        Some(len) => {
+                            output.push(char::REPLACEMENT_CHARACTER);
+                            rest = &invalid[len..];
+                        }
+                        None => {
+                            // The input ends in the middle of a character
+                            self.pending = invalid.to_vec();
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_complete_input() {
+        let mut decoder = Utf8Decoder::new();
+        assert_eq!(decoder.decode("héllo 日本".as_bytes()), "héllo 日本");
+        assert!(decoder.pending.is_empty());
+    }
+
+    #[test]
+    fn test_decode_split_character() {
+        let bytes = "a日b".as_bytes();
+        let mut decoder = Utf8Decoder::new();
+        assert_eq!(decoder.decode(&bytes[..2]), "a");
+        assert_eq!(decoder.pending, &bytes[1..2]);
+        assert_eq!(decoder.decode(&bytes[2..3]), "");
+        assert_eq!(decoder.decode(&bytes[3..]), "日b");
+        assert!(decoder.pending.is_empty());
+    }
+
+    #[test]
+    fn test_decode_invalid_bytes() {
+        let mut decoder = Utf8Decoder::new();
+        assert_eq!(decoder.decode(b"a\xffb\xc3("), "a\u{fffd}b\u{fffd}(");
+        assert!(decoder.pending.is_empty());
+    }
+
+    #[test]
+    fn test_decode_interrupted_character() {
+        let mut decoder = Utf8Decoder::new();
+        assert_eq!(decoder.decode(b"\xe6\x97"), "");
+        // The continuation never arrives
+        assert_eq!(decoder.decode(b"x"), "\u{fffd}x");
+    }
+}
@@ -0,0 +1,132 @@
+use std::io::Write;
+use virtual_tty::{Screen, VirtualTty};
+
+// =============================================================================
+// DECSET 1049 - SAVE CURSOR AND SWITCH TO CLEARED ALTERNATE SCREEN
+// =============================================================================
+
+#[test]
+fn test_alternate_screen_1049_restores_primary_screen() {
+    let mut tty = VirtualTty::new(10, 3);
+    write!(tty.stdout, "$ vim\r\n").unwrap();
+    assert_eq!(tty.active_screen(), Screen::Primary);
+
+    write!(tty.stdout, "\x1b[?1049h").unwrap();
+    assert!(tty.is_alternate_screen());
+    write!(tty.stdout, "\x1b[H~\r\n~\r\n:q").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    ~         \n
+    ~         \n
+    :q        \n
+    ");
+
+    write!(tty.stdout, "\x1b[?1049l").unwrap();
+    assert_eq!(tty.active_screen(), Screen::Primary);
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    $ vim     \n
+              \n
+              \n
+    ");
+}
+
+#[test]
+fn test_alternate_screen_1049_restores_cursor() {
+    let mut tty = VirtualTty::new(10, 3);
+    write!(tty.stdout, "$ less").unwrap();
+    write!(tty.stdout, "\x1b[?1049h\x1b[3;5Hpage").unwrap();
+    assert_eq!(tty.get_cursor_position(), (2, 8));
+
+    write!(tty.stdout, "\x1b[?1049l").unwrap();
+    assert_eq!(tty.get_cursor_position(), (0, 6));
+}
+
+#[test]
+fn test_alternate_screen_1049_starts_cleared() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "\x1b[?1049hfirst\x1b[?1049l").unwrap();
+    write!(tty.stdout, "\x1b[?1049h").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    \n
+    \n
+    ");
+}
+
+// =============================================================================
+// DECSET 47 / 1047
+// =============================================================================
+
+#[test]
+fn test_alternate_screen_47_keeps_content_and_cursor() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "main").unwrap();
+    write!(tty.stdout, "\x1b[?47halt").unwrap();
+    // The cursor is shared between both screens
+    assert_eq!(tty.get_cursor_position(), (0, 7));
+    write!(tty.stdout, "\x1b[?47l").unwrap();
+
+    // Switching back without clearing shows the old alternate content again
+    write!(tty.stdout, "\x1b[?47h").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+        alt   \n
+              \n
+    ");
+}
+
+#[test]
+fn test_alternate_screen_1047_clears_on_exit() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "main").unwrap();
+    write!(tty.stdout, "\x1b[?1047h\x1b[Halt\x1b[?1047l").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    main      \n
+              \n
+    ");
+
+    write!(tty.stdout, "\x1b[?1047h").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    \n
+    \n
+    ");
+}
+
+// =============================================================================
+// INSPECTING BOTH SCREENS
+// =============================================================================
+
+#[test]
+fn test_screen_snapshot_of_inactive_screen() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "shell").unwrap();
+    write!(tty.stdout, "\x1b[?1049h\x1b[Happ").unwrap();
+
+    let primary = tty.get_screen_snapshot(Screen::Primary);
+    insta::assert_snapshot!(primary, @r"
+    shell     \n
+              \n
+    ");
+    let alternate = tty.get_screen_snapshot(Screen::Alternate);
+    insta::assert_snapshot!(alternate, @r"
+    app       \n
+              \n
+    ");
+    assert_eq!(alternate, tty.get_snapshot());
+}
+
+#[test]
+fn test_repeated_mode_switches_are_idempotent() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "shell").unwrap();
+    write!(tty.stdout, "\x1b[?47l\x1b[?47l").unwrap();
+    assert_eq!(tty.active_screen(), Screen::Primary);
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    shell     \n
+              \n
+    ");
+}
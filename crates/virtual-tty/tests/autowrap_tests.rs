@@ -0,0 +1,103 @@
+use std::io::Write;
+use virtual_tty::VirtualTty;
+
+// =============================================================================
+// DEFERRED WRAP
+// =============================================================================
+
+#[test]
+fn test_cursor_stays_in_last_column() {
+    let mut tty = VirtualTty::new(5, 2);
+    write!(tty.stdout, "abcde").unwrap();
+    assert_eq!(tty.get_cursor_position(), (0, 4));
+    write!(tty.stdout, "f").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    abcde\n
+    f    \n
+    ");
+}
+
+#[test]
+fn test_bottom_right_corner_does_not_scroll() {
+    let mut tty = VirtualTty::new(5, 3);
+    write!(tty.stdout, "top\x1b[3;1Hstatu").unwrap();
+    assert_eq!(tty.get_cursor_position(), (2, 4));
+    assert_eq!(tty.get_scrollback_len(), 0);
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    top  \n
+         \n
+    statu\n
+    ");
+}
+
+#[test]
+fn test_newline_after_full_line_does_not_add_blank_line() {
+    let mut tty = VirtualTty::new(5, 3);
+    write!(tty.stdout, "abcde\r\nfghij\r\nk").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    abcde\n
+    fghij\n
+    k    \n
+    ");
+}
+
+#[test]
+fn test_cursor_movement_cancels_pending_wrap() {
+    let mut tty = VirtualTty::new(5, 2);
+    write!(tty.stdout, "abcde\x1b[DX").unwrap();
+    write!(tty.stdout, "\x1b[2;1Hfghij\rY").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    abcXe\n
+    Yghij\n
+    ");
+}
+
+#[test]
+fn test_save_restore_keeps_pending_wrap() {
+    let mut tty = VirtualTty::new(5, 2);
+    write!(tty.stdout, "abcde\x1b7\x1b[H\x1b8f").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    abcde\n
+    f    \n
+    ");
+}
+
+// =============================================================================
+// DECAWM (CSI ? 7 h / CSI ? 7 l)
+// =============================================================================
+
+#[test]
+fn test_autowrap_disabled_overwrites_last_column() {
+    let mut tty = VirtualTty::new(5, 2);
+    write!(tty.stdout, "\x1b[?7labcdefg").unwrap();
+    assert_eq!(tty.get_cursor_position(), (0, 4));
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    abcdg\n
+         \n
+    ");
+}
+
+#[test]
+fn test_autowrap_can_be_enabled_again() {
+    let mut tty = VirtualTty::new(5, 2);
+    write!(tty.stdout, "\x1b[?7labcdefg\x1b[?7hhi").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    abcdh\n
+    i    \n
+    ");
+}
+
+#[test]
+fn test_wide_character_without_autowrap() {
+    let mut tty = VirtualTty::new(5, 1);
+    write!(tty.stdout, "\x1b[?7labcd日").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @"abc日\\n");
+}
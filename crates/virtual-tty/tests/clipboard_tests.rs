@@ -0,0 +1,84 @@
+use std::io::Write;
+use virtual_tty::{ClipboardWrite, VirtualTty};
+
+// =============================================================================
+// OSC 52 WRITES
+// =============================================================================
+
+#[test]
+fn test_copy_to_clipboard() {
+    let mut tty = VirtualTty::new(10, 1);
+    // "selected text" in base64
+    write!(tty.stdout, "\x1b]52;c;c2VsZWN0ZWQgdGV4dA==\x07ok").unwrap();
+    assert_eq!(tty.get_clipboard(), "selected text");
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @"ok        \\n");
+}
+
+#[test]
+fn test_clipboard_history() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b]52;c;Zmlyc3Q=\x1b\\").unwrap();
+    write!(tty.stdout, "\x1b]52;;5pel5pys\x1b\\").unwrap();
+    write!(tty.stdout, "\x1b]52;p;\x1b\\").unwrap();
+    assert_eq!(
+        tty.get_clipboard_history(),
+        vec![
+            ClipboardWrite {
+                selection: "c".to_string(),
+                content: "first".to_string(),
+            },
+            // An empty selection parameter means `s0`
+            ClipboardWrite {
+                selection: "s0".to_string(),
+                content: "日本".to_string(),
+            },
+            // Empty data clears the selection
+            ClipboardWrite {
+                selection: "p".to_string(),
+                content: String::new(),
+            },
+        ]
+    );
+    assert_eq!(tty.get_clipboard(), "");
+}
+
+#[test]
+fn test_large_clipboard_payload_split_across_writes() {
+    let mut tty = VirtualTty::new(10, 1);
+    let payload = "QUFB".repeat(5000);
+    write!(tty.stdout, "\x1b]52;c;{}", &payload[..7000]).unwrap();
+    write!(tty.stdout, "{}\x07x", &payload[7000..]).unwrap();
+    assert_eq!(tty.get_clipboard(), "A".repeat(15000));
+    // None of the payload was printed
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @"x         \\n");
+}
+
+// =============================================================================
+// OSC 52 QUERIES
+// =============================================================================
+
+#[test]
+fn test_query_answers_configured_clipboard() {
+    let mut tty = VirtualTty::new(10, 1);
+    tty.set_clipboard("paste me");
+    write!(tty.stdout, "\x1b]52;c;?\x07").unwrap();
+    assert_eq!(
+        tty.take_responses(),
+        vec!["\x1b]52;c;cGFzdGUgbWU=\x1b\\".to_string()]
+    );
+    assert!(tty.take_responses().is_empty());
+    // Configuring the clipboard is not a write from the program
+    assert!(tty.get_clipboard_history().is_empty());
+}
+
+#[test]
+fn test_query_answers_last_copied_text() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b]52;c;aGk=\x07\x1b]52;c;?\x07").unwrap();
+    assert_eq!(
+        tty.take_responses(),
+        vec!["\x1b]52;c;aGk=\x1b\\".to_string()]
+    );
+}
@@ -8,7 +8,7 @@ use virtual_tty::VirtualTty;
 #[test]
 fn test_complex_cursor_sequence() {
     let mut tty = VirtualTty::new(10, 5);
-    write!(tty.stdout, "Hello\nWorld\nTest").unwrap();
+    write!(tty.stdout, "Hello\r\nWorld\r\nTest").unwrap();
     write!(tty.stdout, "\x1b[2A").unwrap(); // Up 2 lines
     write!(tty.stdout, "\x1b[2C").unwrap(); // Right 2 columns
     write!(tty.stdout, "X").unwrap();
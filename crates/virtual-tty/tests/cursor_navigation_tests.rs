@@ -9,7 +9,7 @@ use virtual_tty::VirtualTty;
 #[test]
 fn test_cursor_up_basic() {
     let mut tty = VirtualTty::new(10, 5);
-    write!(tty.stdout, "Line1\nLine2\nLine3").unwrap();
+    write!(tty.stdout, "Line1\r\nLine2\r\nLine3").unwrap();
     write!(tty.stdout, "\x1b[1A").unwrap(); // Move up 1 line
     write!(tty.stdout, "X").unwrap();
     let snapshot = tty.get_snapshot();
@@ -25,7 +25,7 @@ fn test_cursor_up_basic() {
 #[test]
 fn test_cursor_up_multiple() {
     let mut tty = VirtualTty::new(10, 5);
-    write!(tty.stdout, "Line1\nLine2\nLine3").unwrap();
+    write!(tty.stdout, "Line1\r\nLine2\r\nLine3").unwrap();
     write!(tty.stdout, "\x1b[2A").unwrap(); // Move up 2 lines
     write!(tty.stdout, "X").unwrap();
     let snapshot = tty.get_snapshot();
@@ -41,7 +41,7 @@ fn test_cursor_up_multiple() {
 #[test]
 fn test_cursor_up_no_parameter() {
     let mut tty = VirtualTty::new(10, 5);
-    write!(tty.stdout, "Line1\nLine2").unwrap();
+    write!(tty.stdout, "Line1\r\nLine2").unwrap();
     write!(tty.stdout, "\x1b[A").unwrap(); // Move up 1 line (default)
     write!(tty.stdout, "X").unwrap();
     let snapshot = tty.get_snapshot();
@@ -270,7 +270,7 @@ fn test_absolute_cursor_positioning() {
 #[test]
 fn test_set_cursor_to_row_col() {
     let mut tty = VirtualTty::new(10, 5);
-    write!(tty.stdout, "Hello\nWorld").unwrap();
+    write!(tty.stdout, "Hello\r\nWorld").unwrap();
     write!(tty.stdout, "\x1b[2;3H").unwrap(); // Move to row 2, col 3
     write!(tty.stdout, "X").unwrap();
     let snapshot = tty.get_snapshot();
@@ -286,7 +286,7 @@ fn test_set_cursor_to_row_col() {
 #[test]
 fn test_set_cursor_to_row_col_alt_syntax() {
     let mut tty = VirtualTty::new(10, 5);
-    write!(tty.stdout, "Hello\nWorld").unwrap();
+    write!(tty.stdout, "Hello\r\nWorld").unwrap();
     write!(tty.stdout, "\x1b[2;3f").unwrap(); // Move to row 2, col 3 (f command)
     write!(tty.stdout, "X").unwrap();
     let snapshot = tty.get_snapshot();
@@ -302,7 +302,7 @@ fn test_set_cursor_to_row_col_alt_syntax() {
 #[test]
 fn test_set_cursor_to_home_position() {
     let mut tty = VirtualTty::new(10, 5);
-    write!(tty.stdout, "Hello\nWorld").unwrap();
+    write!(tty.stdout, "Hello\r\nWorld").unwrap();
     write!(tty.stdout, "\x1b[H").unwrap(); // Move to row 1, col 1 (default)
     write!(tty.stdout, "X").unwrap();
     let snapshot = tty.get_snapshot();
@@ -318,7 +318,7 @@ fn test_set_cursor_to_home_position() {
 #[test]
 fn test_set_cursor_partial_coordinates() {
     let mut tty = VirtualTty::new(10, 5);
-    write!(tty.stdout, "Hello\nWorld").unwrap();
+    write!(tty.stdout, "Hello\r\nWorld").unwrap();
     write!(tty.stdout, "\x1b[2;H").unwrap(); // Move to row 2, col 1 (default)
     write!(tty.stdout, "X").unwrap();
     let snapshot = tty.get_snapshot();
@@ -334,11 +334,12 @@ fn test_set_cursor_partial_coordinates() {
 #[test]
 fn test_set_cursor_bounds_clamping() {
     let mut tty = VirtualTty::new(15, 10);
-    write!(tty.stdout, "Hello\nWorld").unwrap();
+    write!(tty.stdout, "Hello\r\nWorld").unwrap();
     write!(tty.stdout, "\x1b[20;30H").unwrap(); // Try to move to row 20, col 30 (should be clamped)
     write!(tty.stdout, "X").unwrap();
     let snapshot = tty.get_snapshot();
     insta::assert_snapshot!(snapshot, @r"
+    Hello          \n
     World          \n
                    \n
                    \n
@@ -348,6 +349,106 @@ fn test_set_cursor_bounds_clamping() {
                    \n
                    \n
                   X\n
-                   \n
     ");
 }
+
+// Cursor Horizontal Absolute (G and ` commands) tests
+#[test]
+fn test_cursor_column_absolute() {
+    let mut tty = VirtualTty::new(10, 3);
+    write!(tty.stdout, "Loading").unwrap();
+    write!(tty.stdout, "\x1b[1GDone").unwrap(); // Spinner-style rewrite of the line
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    Doneing   \n
+              \n
+              \n
+    ");
+}
+
+#[test]
+fn test_cursor_column_absolute_defaults_and_clamping() {
+    let mut tty = VirtualTty::new(10, 3);
+    write!(tty.stdout, "\x1b[2;5H\x1b[GA").unwrap(); // No parameter means column 1
+    assert_eq!(tty.get_cursor_position(), (1, 1));
+    write!(tty.stdout, "\x1b[3`C").unwrap(); // HPA
+    write!(tty.stdout, "\x1b[99G").unwrap();
+    assert_eq!(tty.get_cursor_position(), (1, 9));
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+              \n
+    A C       \n
+              \n
+    ");
+}
+
+// Line Position Absolute (d command) tests
+#[test]
+fn test_cursor_row_absolute() {
+    let mut tty = VirtualTty::new(10, 4);
+    write!(tty.stdout, "abc\x1b[3dX").unwrap(); // Keeps the column
+    write!(tty.stdout, "\x1b[9dY").unwrap(); // Clamped to the last row
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    abc       \n
+              \n
+       X      \n
+        Y     \n
+    ");
+}
+
+// Cursor Next/Previous Line (E and F commands) tests
+#[test]
+fn test_cursor_next_line() {
+    let mut tty = VirtualTty::new(10, 4);
+    write!(tty.stdout, "abc\x1b[EX\x1b[2EY").unwrap();
+    write!(tty.stdout, "\x1b[5E").unwrap(); // Clamped to the last row, no scrolling
+    assert_eq!(tty.get_cursor_position(), (3, 0));
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    abc       \n
+    X         \n
+              \n
+    Y         \n
+    ");
+}
+
+#[test]
+fn test_cursor_previous_line() {
+    let mut tty = VirtualTty::new(10, 4);
+    write!(tty.stdout, "\x1b[4;5H\x1b[FX\x1b[FY\x1b[9FZ").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    Z         \n
+    Y         \n
+    X         \n
+              \n
+    ");
+}
+
+// Horizontal/Vertical Position Relative (a and e commands) tests
+#[test]
+fn test_cursor_position_relative() {
+    let mut tty = VirtualTty::new(10, 4);
+    write!(tty.stdout, "a\x1b[2ab\x1b[eX").unwrap();
+    write!(tty.stdout, "\x1b[99a\x1b[99e").unwrap(); // Clamped to the bottom-right corner
+    assert_eq!(tty.get_cursor_position(), (3, 9));
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    a  b      \n
+        X     \n
+              \n
+              \n
+    ");
+}
+
+#[test]
+fn test_relative_movements_with_huge_count() {
+    let mut tty = VirtualTty::new(10, 4);
+    write!(tty.stdout, "\x1b[2;1H\x1b[18446744073709551615e").unwrap();
+    assert_eq!(tty.get_cursor_position(), (3, 0));
+    write!(tty.stdout, "\x1b[2;1H\x1b[18446744073709551615a").unwrap();
+    assert_eq!(tty.get_cursor_position(), (1, 9));
+    write!(tty.stdout, "\x1b[2;5H\x1b[18446744073709551615E").unwrap();
+    assert_eq!(tty.get_cursor_position(), (3, 0));
+}
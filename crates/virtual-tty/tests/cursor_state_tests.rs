@@ -1,3 +1,5 @@
+#![allow(clippy::write_with_newline)]
+
 use std::io::Write;
 use virtual_tty::VirtualTty;
 
@@ -27,16 +29,16 @@ fn test_cursor_position_tracking_comprehensive() {
     let (row, col) = tty.get_cursor_position();
     assert_eq!((row, col), (0, 5));
 
-    // After newline
+    // After newline, which keeps the column
     write!(tty.stdout, "\n").unwrap();
     let (row, col) = tty.get_cursor_position();
-    assert_eq!((row, col), (1, 0));
+    assert_eq!((row, col), (1, 5));
 
     // After cursor movement
     write!(tty.stdout, "\x1b[1A").unwrap(); // Up 1
     write!(tty.stdout, "\x1b[2C").unwrap(); // Right 2
     let (row, col) = tty.get_cursor_position();
-    assert_eq!((row, col), (0, 2));
+    assert_eq!((row, col), (0, 7));
 }
 
 // =============================================================================
@@ -46,7 +48,7 @@ fn test_cursor_position_tracking_comprehensive() {
 #[test]
 fn test_cursor_tracking_during_scroll() {
     let mut tty = VirtualTty::new(10, 2);
-    write!(tty.stdout, "Line1\nLine2\nLine3").unwrap(); // This should scroll
+    write!(tty.stdout, "Line1\r\nLine2\r\nLine3").unwrap(); // This should scroll
     let (row, col) = tty.get_cursor_position();
     assert_eq!(row, 1); // Should be on last line
     assert_eq!(col, 5); // After "Line3"
@@ -55,7 +57,7 @@ fn test_cursor_tracking_during_scroll() {
 #[test]
 fn test_relative_movement_after_scroll() {
     let mut tty = VirtualTty::new(10, 2);
-    write!(tty.stdout, "Line1\nLine2\nLine3").unwrap(); // This should scroll
+    write!(tty.stdout, "Line1\r\nLine2\r\nLine3").unwrap(); // This should scroll
     write!(tty.stdout, "\x1b[1A").unwrap(); // Move up 1 line
     write!(tty.stdout, "X").unwrap();
     let snapshot = tty.get_snapshot();
@@ -0,0 +1,110 @@
+use std::io::Write;
+use virtual_tty::VirtualTty;
+
+// =============================================================================
+// COMBINING CHARACTERS
+// =============================================================================
+
+#[test]
+fn test_combining_mark_joins_previous_cell() {
+    let mut tty = VirtualTty::new(8, 2);
+    write!(tty.stdout, "caf\u{65}\u{301}!").unwrap();
+    assert_eq!(tty.get_cursor_position(), (0, 5));
+
+    let cell = tty.cell_at(0, 3).unwrap();
+    assert_eq!(cell.ch, 'e');
+    assert_eq!(cell.combining, vec!['\u{301}']);
+    assert_eq!(cell.grapheme(), "e\u{301}");
+    assert_eq!(tty.cell_at(0, 4).unwrap().ch, '!');
+
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    café!   \n
+            \n
+    ");
+}
+
+#[test]
+fn test_combining_mark_in_separate_write() {
+    let mut tty = VirtualTty::new(8, 1);
+    write!(tty.stdout, "n").unwrap();
+    write!(tty.stdout, "\u{303}o").unwrap();
+    assert_eq!(tty.cell_at(0, 0).unwrap().grapheme(), "n\u{303}");
+    assert_eq!(tty.cell_at(0, 1).unwrap().ch, 'o');
+}
+
+#[test]
+fn test_multiple_combining_marks() {
+    let mut tty = VirtualTty::new(8, 1);
+    write!(tty.stdout, "a\u{300}\u{316}b").unwrap();
+    assert_eq!(
+        tty.cell_at(0, 0).unwrap().combining,
+        vec!['\u{300}', '\u{316}']
+    );
+    assert_eq!(tty.get_cursor_position(), (0, 2));
+}
+
+#[test]
+fn test_combining_mark_after_wrap() {
+    let mut tty = VirtualTty::new(4, 2);
+    write!(tty.stdout, "abce\u{301}x").unwrap();
+    assert_eq!(tty.cell_at(0, 3).unwrap().grapheme(), "e\u{301}");
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    abcé\n
+    x   \n
+    ");
+}
+
+#[test]
+fn test_combining_mark_without_base_is_dropped() {
+    let mut tty = VirtualTty::new(4, 2);
+    write!(tty.stdout, "a\r\n\u{301}b").unwrap();
+    assert!(tty.cell_at(0, 0).unwrap().combining.is_empty());
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    a   \n
+    b   \n
+    ");
+}
+
+#[test]
+fn test_legacy_computing_symbol_is_not_combining() {
+    // U+1CC00 lies between two ranges of combining marks
+    let mut tty = VirtualTty::new(4, 1);
+    write!(tty.stdout, "a\u{1CC00}b").unwrap();
+    assert_eq!(tty.cell_at(0, 1).unwrap().ch, '\u{1CC00}');
+    assert_eq!(tty.get_cursor_position(), (0, 3));
+}
+
+// =============================================================================
+// EMOJI SEQUENCES
+// =============================================================================
+
+#[test]
+fn test_variation_selector() {
+    let mut tty = VirtualTty::new(6, 1);
+    write!(tty.stdout, "\u{2764}\u{fe0f}x").unwrap();
+    assert_eq!(tty.cell_at(0, 0).unwrap().grapheme(), "\u{2764}\u{fe0f}");
+    assert_eq!(tty.cell_at(0, 1).unwrap().ch, 'x');
+}
+
+#[test]
+fn test_skin_tone_modifier() {
+    let mut tty = VirtualTty::new(6, 1);
+    write!(tty.stdout, "👋🏽!").unwrap();
+    assert_eq!(tty.cell_at(0, 0).unwrap().grapheme(), "👋🏽");
+    assert_eq!(tty.get_cursor_position(), (0, 3));
+}
+
+#[test]
+fn test_zwj_sequence_takes_one_wide_cell() {
+    let mut tty = VirtualTty::new(6, 1);
+    write!(tty.stdout, "👨\u{200d}👩\u{200d}👧!").unwrap();
+    let cell = tty.cell_at(0, 0).unwrap();
+    assert_eq!(cell.grapheme(), "👨\u{200d}👩\u{200d}👧");
+    assert_eq!(cell.width, 2);
+    assert_eq!(tty.cell_at(0, 2).unwrap().ch, '!');
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @"👨‍👩‍👧!   \\n");
+}
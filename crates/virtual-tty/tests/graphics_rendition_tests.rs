@@ -0,0 +1,200 @@
+use std::io::Write;
+use virtual_tty::{CellAttributes, Color, VirtualTty};
+
+// =============================================================================
+// COLORS
+// =============================================================================
+
+#[test]
+fn test_foreground_color_is_stored_per_cell() {
+    let mut tty = VirtualTty::new(10, 3);
+    write!(tty.stdout, "\x1b[31mError\x1b[0m ok").unwrap();
+
+    let cell = tty.cell_at(0, 0).unwrap();
+    assert_eq!(cell.ch, 'E');
+    assert_eq!(cell.attributes.foreground, Color::RED);
+
+    let cell = tty.cell_at(0, 4).unwrap();
+    assert_eq!(cell.ch, 'r');
+    assert_eq!(cell.attributes.foreground, Color::RED);
+
+    // SGR 0 resets the pen for the following text
+    let cell = tty.cell_at(0, 6).unwrap();
+    assert_eq!(cell.ch, 'o');
+    assert!(cell.attributes.is_default());
+}
+
+#[test]
+fn test_background_color_and_default_colors() {
+    let mut tty = VirtualTty::new(10, 3);
+    write!(tty.stdout, "\x1b[32;44mA\x1b[39mB\x1b[49mC").unwrap();
+
+    let a = tty.cell_at(0, 0).unwrap().attributes;
+    assert_eq!(a.foreground, Color::GREEN);
+    assert_eq!(a.background, Color::BLUE);
+
+    let b = tty.cell_at(0, 1).unwrap().attributes;
+    assert_eq!(b.foreground, Color::Default);
+    assert_eq!(b.background, Color::BLUE);
+
+    let c = tty.cell_at(0, 2).unwrap().attributes;
+    assert!(c.is_default());
+}
+
+#[test]
+fn test_colors_do_not_affect_snapshot() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "\x1b[1;31mError\x1b[0m: x").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    Error: x  \n
+              \n
+    ");
+}
+
+// =============================================================================
+// BRIGHT, 256-COLOR AND TRUECOLOR
+// =============================================================================
+
+#[test]
+fn test_bright_colors() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b[91;104mA\x1b[97;100mB").unwrap();
+
+    let a = tty.cell_at(0, 0).unwrap().attributes;
+    assert_eq!(a.foreground, Color::BRIGHT_RED);
+    assert_eq!(a.background, Color::BRIGHT_BLUE);
+
+    let b = tty.cell_at(0, 1).unwrap().attributes;
+    assert_eq!(b.foreground, Color::BRIGHT_WHITE);
+    assert_eq!(b.background, Color::BRIGHT_BLACK);
+}
+
+#[test]
+fn test_256_colors() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b[38;5;208;48;5;17mA").unwrap();
+
+    let a = tty.cell_at(0, 0).unwrap().attributes;
+    assert_eq!(a.foreground, Color::Indexed(208));
+    assert_eq!(a.background, Color::Indexed(17));
+}
+
+#[test]
+fn test_truecolor() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b[38;2;255;128;0;48;2;10;20;30;1mA").unwrap();
+
+    let a = tty.cell_at(0, 0).unwrap().attributes;
+    assert_eq!(a.foreground, Color::Rgb(255, 128, 0));
+    assert_eq!(a.background, Color::Rgb(10, 20, 30));
+    // Parameters after the color components are still applied
+    assert!(a.bold);
+}
+
+#[test]
+fn test_colon_separated_colors() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b[38:2::1:2:3mA").unwrap(); // ITU form with empty color space
+    write!(tty.stdout, "\x1b[38:2:4:5:6mB").unwrap(); // xterm shorthand
+    write!(tty.stdout, "\x1b[48:5:99;4mC").unwrap();
+
+    assert_eq!(
+        tty.cell_at(0, 0).unwrap().attributes.foreground,
+        Color::Rgb(1, 2, 3)
+    );
+    assert_eq!(
+        tty.cell_at(0, 1).unwrap().attributes.foreground,
+        Color::Rgb(4, 5, 6)
+    );
+    let c = tty.cell_at(0, 2).unwrap().attributes;
+    assert_eq!(c.background, Color::Indexed(99));
+    assert!(c.underline);
+}
+
+#[test]
+fn test_underline_color_is_skipped() {
+    let mut tty = VirtualTty::new(10, 1);
+    // The components of SGR 58 must not be interpreted as attributes (1 = bold, 3 = italic)
+    write!(tty.stdout, "\x1b[58;2;1;3;1mA").unwrap();
+
+    assert!(tty.cell_at(0, 0).unwrap().attributes.is_default());
+}
+
+#[test]
+fn test_truncated_extended_color_is_ignored() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b[31m\x1b[38;2;255mA").unwrap();
+
+    assert_eq!(tty.cell_at(0, 0).unwrap().attributes.foreground, Color::RED);
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    A         \n
+    ");
+}
+
+// =============================================================================
+// TEXT ATTRIBUTES
+// =============================================================================
+
+#[test]
+fn test_all_attributes_can_be_set() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b[1;2;3;4;5;7;8;9mX").unwrap();
+
+    let attributes = tty.cell_at(0, 0).unwrap().attributes;
+    assert_eq!(
+        attributes,
+        CellAttributes {
+            bold: true,
+            dim: true,
+            italic: true,
+            underline: true,
+            blink: true,
+            reverse: true,
+            hidden: true,
+            strikethrough: true,
+            ..CellAttributes::default()
+        }
+    );
+}
+
+#[test]
+fn test_attributes_can_be_turned_off_individually() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b[1;3;4;7mA\x1b[22;24mB\x1b[23;27mC").unwrap();
+
+    let a = tty.cell_at(0, 0).unwrap().attributes;
+    assert!(a.bold && a.italic && a.underline && a.reverse);
+
+    let b = tty.cell_at(0, 1).unwrap().attributes;
+    assert!(!b.bold && b.italic && !b.underline && b.reverse);
+
+    let c = tty.cell_at(0, 2).unwrap().attributes;
+    assert!(c.is_default());
+}
+
+#[test]
+fn test_empty_sgr_resets_pen() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b[7mA\x1b[mB").unwrap();
+
+    assert!(tty.cell_at(0, 0).unwrap().attributes.reverse);
+    assert!(tty.cell_at(0, 1).unwrap().attributes.is_default());
+}
+
+#[test]
+fn test_pen_is_shared_between_stdout_and_stderr() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stderr, "\x1b[31m").unwrap();
+    write!(tty.stdout, "A").unwrap();
+
+    assert_eq!(tty.cell_at(0, 0).unwrap().attributes.foreground, Color::RED);
+}
+
+#[test]
+fn test_cell_at_out_of_bounds() {
+    let tty = VirtualTty::new(10, 3);
+    assert!(tty.cell_at(3, 0).is_none());
+    assert!(tty.cell_at(0, 10).is_none());
+}
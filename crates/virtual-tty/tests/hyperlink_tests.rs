@@ -0,0 +1,117 @@
+use std::io::Write;
+use virtual_tty::{Hyperlink, HyperlinkSpan, VirtualTty};
+
+// =============================================================================
+// OSC 8 HYPERLINKS
+// =============================================================================
+
+#[test]
+fn test_hyperlink_is_not_printed() {
+    let mut tty = VirtualTty::new(20, 2);
+    write!(
+        tty.stdout,
+        "see \x1b]8;;https://docs.rs\x1b\\docs\x1b]8;;\x1b\\ now"
+    )
+    .unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    see docs now        \n
+                        \n
+    ");
+}
+
+#[test]
+fn test_hyperlinks_with_text_span() {
+    let mut tty = VirtualTty::new(20, 2);
+    write!(
+        tty.stdout,
+        "\x1b]8;id=src;file:///src/main.rs\x07main.rs\x1b]8;;\x07:12\r\n"
+    )
+    .unwrap();
+    write!(tty.stdout, "  \x1b]8;;https://a.io\x07日本\x1b]8;;\x07").unwrap();
+    assert_eq!(
+        tty.get_hyperlinks(),
+        vec![
+            HyperlinkSpan {
+                hyperlink: Hyperlink {
+                    uri: "file:///src/main.rs".to_string(),
+                    id: Some("src".to_string()),
+                },
+                row: 0,
+                columns: 0..7,
+                text: "main.rs".to_string(),
+            },
+            HyperlinkSpan {
+                hyperlink: Hyperlink {
+                    uri: "https://a.io".to_string(),
+                    id: None,
+                },
+                row: 1,
+                columns: 2..6,
+                text: "日本".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_hyperlink_stored_in_cells() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b]8;;https://a.io\x07ab\x1b]8;;\x07c").unwrap();
+    let cell = tty.cell_at(0, 1).unwrap();
+    assert_eq!(cell.hyperlink.unwrap().uri, "https://a.io");
+    assert!(tty.cell_at(0, 2).unwrap().hyperlink.is_none());
+}
+
+#[test]
+fn test_wrapped_hyperlink_gives_one_span_per_line() {
+    let mut tty = VirtualTty::new(6, 2);
+    write!(
+        tty.stdout,
+        "ab\x1b]8;id=1;https://a.io\x07linked\x1b]8;;\x07"
+    )
+    .unwrap();
+    let spans = tty.get_hyperlinks();
+    assert_eq!(spans.len(), 2);
+    assert_eq!((spans[0].row, spans[0].columns.clone()), (0, 2..6));
+    assert_eq!(spans[0].text, "link");
+    assert_eq!((spans[1].row, spans[1].columns.clone()), (1, 0..2));
+    assert_eq!(spans[1].text, "ed");
+    assert_eq!(spans[0].hyperlink, spans[1].hyperlink);
+}
+
+#[test]
+fn test_erasing_removes_hyperlink() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b]8;;https://a.io\x07link\x1b[2K").unwrap();
+    // The link stays active for new text, but erased cells lose it
+    assert!(tty.get_hyperlinks().is_empty());
+}
+
+#[test]
+fn test_styled_snapshot_marks_hyperlinks() {
+    let mut tty = VirtualTty::new(20, 2);
+    write!(
+        tty.stdout,
+        "\x1b[31mError\x1b[0m: \x1b]8;;https://a.io\x07\x1b[4mdocs\x1b[0m\x1b]8;;\x07\r\n"
+    )
+    .unwrap();
+    write!(tty.stdout, "\x1b]8;;file:///tmp\x07/tmp").unwrap();
+    let snapshot = tty.get_styled_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    [fg=red]Error[/]: [link=https://a.io][underline]docs[/][/link]         \n
+    [link=file:///tmp]/tmp[/link]                \n
+    ");
+}
+
+#[test]
+fn test_styled_snapshot_nests_style_runs_crossing_links() {
+    let mut tty = VirtualTty::new(12, 1);
+    write!(
+        tty.stdout,
+        "\x1b]8;;u\x07\x1b[1mab\x1b]8;;\x07cd\x1b[0m \x1b[3me\x1b]8;;v\x07f"
+    )
+    .unwrap();
+    let snapshot = tty.get_styled_snapshot();
+    insta::assert_snapshot!(snapshot, @"[link=u][bold]ab[/][/link][bold]cd[/] [italic]e[/][link=v][italic]f[/][/link]     \\n");
+}
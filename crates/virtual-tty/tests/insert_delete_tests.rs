@@ -0,0 +1,168 @@
+use std::io::Write;
+use virtual_tty::{Color, VirtualTty};
+
+// =============================================================================
+// CHARACTER EDITING (ICH, DCH, ECH)
+// =============================================================================
+
+#[test]
+fn test_insert_chars() {
+    let mut tty = VirtualTty::new(12, 1);
+    write!(tty.stdout, "helloworld\x1b[1;6H\x1b[2@, ").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    hello, world\n
+    ");
+}
+
+#[test]
+fn test_insert_chars_default_count() {
+    let mut tty = VirtualTty::new(6, 1);
+    write!(tty.stdout, "abc\x1b[1;1H\x1b[@").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+     abc  \n
+    ");
+}
+
+#[test]
+fn test_delete_chars() {
+    let mut tty = VirtualTty::new(14, 1);
+    write!(tty.stdout, "hello, world\x1b[1;6H\x1b[2P").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    helloworld    \n
+    ");
+}
+
+#[test]
+fn test_delete_more_chars_than_remaining() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "abcdef\x1b[1;3H\x1b[100P").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    ab        \n
+    ");
+}
+
+#[test]
+fn test_erase_chars() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "abcdef\x1b[1;2H\x1b[3X").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    a   ef    \n
+    ");
+    // ECH does not move the cursor
+    assert_eq!(tty.get_cursor_position(), (0, 1));
+}
+
+#[test]
+fn test_erase_chars_with_huge_count() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "abcdef\x1b[1;2H\x1b[18446744073709551615X").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    a         \n
+    ");
+}
+
+#[test]
+fn test_shifted_cells_keep_attributes() {
+    let mut tty = VirtualTty::new(6, 1);
+    write!(tty.stdout, "a\x1b[31mb\x1b[0mc\x1b[1;1H\x1b[2@").unwrap();
+    let moved = tty.cell_at(0, 3).unwrap();
+    assert_eq!(moved.ch, 'b');
+    assert_eq!(moved.attributes.foreground, Color::RED);
+    assert!(tty.cell_at(0, 0).unwrap().attributes.is_default());
+
+    write!(tty.stdout, "\x1b[3P").unwrap();
+    let moved = tty.cell_at(0, 0).unwrap();
+    assert_eq!(moved.ch, 'b');
+    assert_eq!(moved.attributes.foreground, Color::RED);
+}
+
+// =============================================================================
+// LINE EDITING (IL, DL)
+// =============================================================================
+
+#[test]
+fn test_insert_lines() {
+    let mut tty = VirtualTty::new(6, 4);
+    write!(tty.stdout, "one\r\ntwo\r\nthree\r\nfour").unwrap();
+    write!(tty.stdout, "\x1b[2;3H\x1b[2L").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    one   \n
+          \n
+          \n
+    two   \n
+    ");
+    // IL moves the cursor to the first column
+    assert_eq!(tty.get_cursor_position(), (1, 0));
+}
+
+#[test]
+fn test_delete_lines() {
+    let mut tty = VirtualTty::new(6, 4);
+    write!(tty.stdout, "one\r\ntwo\r\nthree\r\nfour").unwrap();
+    write!(tty.stdout, "\x1b[2;3H\x1b[M").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    one   \n
+    three \n
+    four  \n
+          \n
+    ");
+}
+
+#[test]
+fn test_delete_lines_does_not_feed_scrollback() {
+    let mut tty = VirtualTty::new(5, 2);
+    write!(tty.stdout, "one\r\ntwo\x1b[H\x1b[M").unwrap();
+    assert_eq!(tty.get_scrollback_len(), 0);
+}
+
+#[test]
+fn test_insert_lines_respects_scroll_region() {
+    let mut tty = VirtualTty::new(7, 5);
+    write!(tty.stdout, "head\r\na\r\nb\r\nc\r\nstatus").unwrap();
+    write!(tty.stdout, "\x1b[2;4r\x1b[2;1H\x1b[L").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    head   \n
+           \n
+    a      \n
+    b      \n
+    status \n
+    ");
+}
+
+#[test]
+fn test_delete_lines_respects_scroll_region() {
+    let mut tty = VirtualTty::new(7, 5);
+    write!(tty.stdout, "head\r\na\r\nb\r\nc\r\nstatus").unwrap();
+    write!(tty.stdout, "\x1b[2;4r\x1b[2;1H\x1b[2M").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    head   \n
+    c      \n
+           \n
+           \n
+    status \n
+    ");
+}
+
+#[test]
+fn test_line_editing_outside_scroll_region_is_ignored() {
+    let mut tty = VirtualTty::new(6, 4);
+    write!(tty.stdout, "a\r\nb\r\nc\r\nd").unwrap();
+    write!(tty.stdout, "\x1b[1;2r\x1b[4;1H\x1b[L\x1b[3;1H\x1b[M").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    a     \n
+    b     \n
+    c     \n
+    d     \n
+    ");
+}
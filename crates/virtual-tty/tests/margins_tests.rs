@@ -0,0 +1,170 @@
+use std::io::Write;
+use virtual_tty::VirtualTty;
+
+fn fill_screen(tty: &mut virtual_tty::VirtualTtyStreams) {
+    write!(
+        tty.stdout,
+        "0123456789\r\nabcdefghij\r\nABCDEFGHIJ\r\nklmnopqrst"
+    )
+    .unwrap();
+}
+
+// =============================================================================
+// DECOM (CSI ? 6 h / CSI ? 6 l)
+// =============================================================================
+
+#[test]
+fn test_origin_mode_homes_to_scroll_region() {
+    let mut tty = VirtualTty::new(10, 6);
+    write!(tty.stdout, "\x1b[3;5r\x1b[?6h").unwrap();
+    assert_eq!(tty.get_cursor_position(), (2, 0));
+
+    write!(tty.stdout, "\x1b[2;4H").unwrap();
+    assert_eq!(tty.get_cursor_position(), (3, 3));
+
+    // The cursor cannot be addressed outside of the region
+    write!(tty.stdout, "\x1b[9;1H").unwrap();
+    assert_eq!(tty.get_cursor_position(), (4, 0));
+    write!(tty.stdout, "\x1b[1d").unwrap();
+    assert_eq!(tty.get_cursor_position(), (2, 0));
+
+    write!(tty.stdout, "\x1b[?6l").unwrap();
+    assert_eq!(tty.get_cursor_position(), (0, 0));
+}
+
+#[test]
+fn test_origin_mode_is_saved_with_cursor() {
+    let mut tty = VirtualTty::new(10, 6);
+    write!(tty.stdout, "\x1b[3;5r\x1b[?6h\x1b7\x1b[?6l\x1b8\x1b[HX").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+              \n
+              \n
+    X         \n
+              \n
+              \n
+              \n
+    ");
+}
+
+#[test]
+fn test_relative_movement_stops_at_scroll_region() {
+    let mut tty = VirtualTty::new(10, 6);
+    write!(tty.stdout, "\x1b[2;4r\x1b[3;1H\x1b[9A").unwrap();
+    assert_eq!(tty.get_cursor_position(), (1, 0));
+    write!(tty.stdout, "\x1b[9B").unwrap();
+    assert_eq!(tty.get_cursor_position(), (3, 0));
+    // Above or below the region the movement can go to the edge of the screen
+    write!(tty.stdout, "\x1b[1;1H\x1b[9A").unwrap();
+    assert_eq!(tty.get_cursor_position(), (0, 0));
+    write!(tty.stdout, "\x1b[6;1H\x1b[9B").unwrap();
+    assert_eq!(tty.get_cursor_position(), (5, 0));
+    write!(tty.stdout, "\x1b[9A").unwrap();
+    assert_eq!(tty.get_cursor_position(), (1, 0));
+}
+
+// =============================================================================
+// DECLRMM / DECSLRM (CSI ? 69 h / CSI Pl ; Pr s)
+// =============================================================================
+
+#[test]
+fn test_csi_s_saves_cursor_without_left_right_margin_mode() {
+    let mut tty = VirtualTty::new(10, 4);
+    write!(tty.stdout, "\x1b[2;3H\x1b[5;8s\x1b[H\x1b[u").unwrap();
+    assert_eq!(tty.get_cursor_position(), (1, 2));
+}
+
+#[test]
+fn test_text_wraps_at_right_margin() {
+    let mut tty = VirtualTty::new(10, 4);
+    write!(tty.stdout, "\x1b[?69h\x1b[3;6s").unwrap();
+    assert_eq!(tty.get_cursor_position(), (0, 0));
+    write!(tty.stdout, "\x1b[1;3Habcdefg").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+      abcd    \n
+      efg     \n
+              \n
+              \n
+    ");
+}
+
+#[test]
+fn test_scrolling_inside_margins() {
+    let mut tty = VirtualTty::new(10, 4);
+    fill_screen(&mut tty);
+    writeln!(tty.stdout, "\x1b[?69h\x1b[3;6s\x1b[4;3H").unwrap();
+    // The line feed returned to the left margin and scrolled the rectangle only
+    assert_eq!(tty.get_cursor_position(), (3, 2));
+    assert_eq!(tty.get_scrollback_len(), 0);
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    01cdef6789\n
+    abCDEFghij\n
+    ABmnopGHIJ\n
+    kl    qrst\n
+    ");
+}
+
+#[test]
+fn test_scroll_down_inside_margins() {
+    let mut tty = VirtualTty::new(10, 4);
+    fill_screen(&mut tty);
+    write!(tty.stdout, "\x1b[?69h\x1b[2;4r\x1b[5;8s\x1b[2T").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    0123456789\n
+    abcd    ij\n
+    ABCD    IJ\n
+    klmnefghst\n
+    ");
+}
+
+#[test]
+fn test_insert_and_delete_inside_margins() {
+    let mut tty = VirtualTty::new(10, 4);
+    fill_screen(&mut tty);
+    write!(tty.stdout, "\x1b[?69h\x1b[3;6s\x1b[2;4H\x1b[L").unwrap();
+    write!(tty.stdout, "\x1b[1;3H\x1b[2P").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    0145  6789\n
+    ab    ghij\n
+    ABcdefGHIJ\n
+    klCDEFqrst\n
+    ");
+}
+
+#[test]
+fn test_origin_mode_with_left_right_margins() {
+    let mut tty = VirtualTty::new(10, 4);
+    write!(tty.stdout, "\x1b[?69h\x1b[2;3r\x1b[4;7s\x1b[?6h").unwrap();
+    assert_eq!(tty.get_cursor_position(), (1, 3));
+    write!(tty.stdout, "\x1b[2;9H").unwrap();
+    assert_eq!(tty.get_cursor_position(), (2, 6));
+    write!(tty.stdout, "\x1b[1G").unwrap();
+    assert_eq!(tty.get_cursor_position(), (2, 3));
+}
+
+#[test]
+fn test_origin_mode_clamps_huge_positions_to_margins() {
+    let mut tty = VirtualTty::new(10, 4);
+    write!(tty.stdout, "\x1b[?69h\x1b[2;3r\x1b[3;6s\x1b[?6h").unwrap();
+    write!(tty.stdout, "\x1b[18446744073709551615G").unwrap();
+    assert_eq!(tty.get_cursor_position(), (1, 5));
+    write!(tty.stdout, "\x1b[18446744073709551615`").unwrap();
+    assert_eq!(tty.get_cursor_position(), (1, 5));
+    write!(tty.stdout, "\x1b[18446744073709551615d").unwrap();
+    assert_eq!(tty.get_cursor_position(), (2, 5));
+}
+
+#[test]
+fn test_resetting_left_right_margin_mode_clears_margins() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "\x1b[?69h\x1b[3;6s\x1b[?69l\x1b[1;3Habcdefg").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+      abcdefg \n
+              \n
+    ");
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::write_with_newline)]
+
 use std::io::Write;
 use virtual_tty::VirtualTty;
 
@@ -8,7 +10,7 @@ use virtual_tty::VirtualTty;
 #[test]
 fn test_mixed_complex_cursor_sequence() {
     let mut tty = VirtualTty::new(10, 5);
-    write!(tty.stdout, "Hello\nWorld\nTest").unwrap();
+    write!(tty.stdout, "Hello\r\nWorld\r\nTest").unwrap();
     write!(tty.stdout, "\x1b[2A").unwrap(); // Up 2 lines
     write!(tty.stderr, "\x1b[2C").unwrap(); // Right 2 columns
     write!(tty.stdout, "X").unwrap();
@@ -32,8 +34,8 @@ fn test_mixed_interleaved_output() {
     write!(tty.stderr, "ERROR: ").unwrap();
     write!(tty.stdout, "ls -la").unwrap();
     write!(tty.stderr, "Permission denied").unwrap();
-    write!(tty.stdout, "\n").unwrap();
-    write!(tty.stderr, "\n").unwrap();
+    write!(tty.stdout, "\r\n").unwrap();
+    write!(tty.stderr, "\r\n").unwrap();
     write!(tty.stdout, "Exit code: 1").unwrap();
     let snapshot = tty.get_snapshot();
     insta::assert_snapshot!(snapshot, @r"
@@ -61,9 +63,9 @@ fn test_mixed_ansi_sequences() {
 #[test]
 fn test_mixed_multiline_output() {
     let mut tty = VirtualTty::new(12, 4);
-    write!(tty.stdout, "Line 1\n").unwrap();
-    write!(tty.stderr, "Error 1\n").unwrap();
-    write!(tty.stdout, "Line 2\n").unwrap();
+    write!(tty.stdout, "Line 1\r\n").unwrap();
+    write!(tty.stderr, "Error 1\r\n").unwrap();
+    write!(tty.stdout, "Line 2\r\n").unwrap();
     write!(tty.stderr, "Error 2").unwrap();
     let snapshot = tty.get_snapshot();
     insta::assert_snapshot!(snapshot, @r"
@@ -107,9 +109,9 @@ fn test_mixed_line_wrapping() {
 #[test]
 fn test_mixed_scrolling_behavior() {
     let mut tty = VirtualTty::new(10, 2);
-    write!(tty.stdout, "Line1\n").unwrap();
-    write!(tty.stderr, "Line2\n").unwrap();
-    write!(tty.stdout, "Line3\n").unwrap();
+    write!(tty.stdout, "Line1\r\n").unwrap();
+    write!(tty.stderr, "Line2\r\n").unwrap();
+    write!(tty.stdout, "Line3\r\n").unwrap();
     write!(tty.stderr, "Line4").unwrap();
     let snapshot = tty.get_snapshot();
     insta::assert_snapshot!(snapshot, @r"
@@ -151,9 +153,9 @@ fn test_mixed_rapid_alternation() {
 #[test]
 fn test_mixed_command_error_pattern() {
     let mut tty = VirtualTty::new(25, 4);
-    write!(tty.stdout, "$ command --option\n").unwrap();
-    write!(tty.stderr, "ERROR: Invalid option\n").unwrap();
-    write!(tty.stdout, "Usage: command [args]\n").unwrap();
+    write!(tty.stdout, "$ command --option\r\n").unwrap();
+    write!(tty.stderr, "ERROR: Invalid option\r\n").unwrap();
+    write!(tty.stdout, "Usage: command [args]\r\n").unwrap();
     write!(tty.stderr, "Exit: 1").unwrap();
     let snapshot = tty.get_snapshot();
     insta::assert_snapshot!(snapshot, @r"
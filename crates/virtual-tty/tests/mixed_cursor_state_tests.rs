@@ -1,3 +1,5 @@
+#![allow(clippy::write_with_newline)]
+
 use std::io::Write;
 use virtual_tty::VirtualTty;
 
@@ -37,16 +39,16 @@ fn test_mixed_cursor_position_tracking_comprehensive() {
     let (row, col) = tty.get_cursor_position();
     assert_eq!((row, col), (0, 11));
 
-    // After newline from stdout
+    // After newline from stdout, which keeps the column
     write!(tty.stdout, "\n").unwrap();
     let (row, col) = tty.get_cursor_position();
-    assert_eq!((row, col), (1, 0));
+    assert_eq!((row, col), (1, 11));
 
     // After cursor movement from stderr
     write!(tty.stderr, "\x1b[1A").unwrap(); // Up 1
     write!(tty.stderr, "\x1b[2C").unwrap(); // Right 2
     let (row, col) = tty.get_cursor_position();
-    assert_eq!((row, col), (0, 2));
+    assert_eq!((row, col), (0, 13));
 }
 
 #[test]
@@ -67,11 +69,11 @@ fn test_mixed_cursor_alternating_streams() {
 
     write!(tty.stderr, "\n").unwrap();
     let (row, col) = tty.get_cursor_position();
-    assert_eq!((row, col), (1, 0));
+    assert_eq!((row, col), (1, 3));
 
     write!(tty.stdout, "D").unwrap();
     let (row, col) = tty.get_cursor_position();
-    assert_eq!((row, col), (1, 1));
+    assert_eq!((row, col), (1, 4));
 }
 
 // =============================================================================
@@ -81,8 +83,8 @@ fn test_mixed_cursor_alternating_streams() {
 #[test]
 fn test_mixed_cursor_tracking_during_scroll() {
     let mut tty = VirtualTty::new(10, 2);
-    write!(tty.stdout, "Line1\n").unwrap();
-    write!(tty.stderr, "Line2\n").unwrap();
+    write!(tty.stdout, "Line1\r\n").unwrap();
+    write!(tty.stderr, "Line2\r\n").unwrap();
     write!(tty.stdout, "Line3").unwrap(); // This should scroll
     let (row, col) = tty.get_cursor_position();
     assert_eq!(row, 1); // Should be on last line
@@ -92,8 +94,8 @@ fn test_mixed_cursor_tracking_during_scroll() {
 #[test]
 fn test_mixed_relative_movement_after_scroll() {
     let mut tty = VirtualTty::new(10, 2);
-    write!(tty.stdout, "Line1\n").unwrap();
-    write!(tty.stderr, "Line2\n").unwrap();
+    write!(tty.stdout, "Line1\r\n").unwrap();
+    write!(tty.stderr, "Line2\r\n").unwrap();
     write!(tty.stdout, "Line3").unwrap(); // This should scroll
     write!(tty.stderr, "\x1b[1A").unwrap(); // Move up 1 line
     write!(tty.stdout, "X").unwrap();
@@ -107,8 +109,8 @@ fn test_mixed_relative_movement_after_scroll() {
 #[test]
 fn test_mixed_scroll_with_cursor_movements() {
     let mut tty = VirtualTty::new(8, 2);
-    write!(tty.stdout, "First\n").unwrap();
-    write!(tty.stderr, "Second\n").unwrap();
+    write!(tty.stdout, "First\r\n").unwrap();
+    write!(tty.stderr, "Second\r\n").unwrap();
     write!(tty.stdout, "Third").unwrap(); // Scroll
     write!(tty.stderr, "\x1b[1A").unwrap(); // Up 1
     write!(tty.stdout, "\x1b[3D").unwrap(); // Back 3
@@ -166,12 +168,12 @@ fn test_mixed_newline_handling() {
     write!(tty.stdout, "Line1").unwrap();
     write!(tty.stderr, "\n").unwrap();
     let (row, col) = tty.get_cursor_position();
-    assert_eq!((row, col), (1, 0));
+    assert_eq!((row, col), (1, 5));
 
-    write!(tty.stdout, "Line2").unwrap();
+    write!(tty.stdout, "\rLine2").unwrap();
     write!(tty.stderr, "\n").unwrap();
     let (row, col) = tty.get_cursor_position();
-    assert_eq!((row, col), (2, 0));
+    assert_eq!((row, col), (2, 5));
 
     let snapshot = tty.get_snapshot();
     insta::assert_snapshot!(snapshot, @r"
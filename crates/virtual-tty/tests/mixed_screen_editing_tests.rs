@@ -1,3 +1,5 @@
+#![allow(clippy::write_with_newline)]
+
 use std::io::Write;
 use virtual_tty::VirtualTty;
 
@@ -95,7 +97,7 @@ fn test_mixed_clear_line_beginning() {
 #[test]
 fn test_mixed_clear_entire_line() {
     let mut tty = VirtualTty::new(10, 3);
-    write!(tty.stdout, "Hello\nWorld\nTest").unwrap();
+    write!(tty.stdout, "Hello\r\nWorld\r\nTest").unwrap();
     write!(tty.stderr, "\x1b[2A").unwrap(); // Up 2 lines
     write!(tty.stdout, "\x1b[2K").unwrap(); // Clear entire line
     write!(tty.stderr, "New").unwrap();
@@ -110,7 +112,7 @@ fn test_mixed_clear_entire_line() {
 #[test]
 fn test_mixed_multiple_clear_operations() {
     let mut tty = VirtualTty::new(12, 4);
-    write!(tty.stdout, "Line1\nLine2\nLine3\nLine4").unwrap();
+    write!(tty.stdout, "Line1\r\nLine2\r\nLine3\r\nLine4").unwrap();
     write!(tty.stderr, "\x1b[2A").unwrap(); // Up 2 lines
     write!(tty.stdout, "\x1b[K").unwrap(); // Clear to end
     write!(tty.stderr, "A").unwrap();
@@ -129,7 +131,7 @@ fn test_mixed_multiple_clear_operations() {
 #[test]
 fn test_mixed_clear_with_cursor_positioning() {
     let mut tty = VirtualTty::new(8, 3);
-    write!(tty.stdout, "ABCD\nEFGH\nIJKL").unwrap();
+    write!(tty.stdout, "ABCD\r\nEFGH\r\nIJKL").unwrap();
     write!(tty.stderr, "\x1b[2;3H").unwrap(); // Position 2,3
     write!(tty.stdout, "\x1b[K").unwrap(); // Clear to end
     write!(tty.stderr, "X").unwrap();
@@ -233,8 +235,8 @@ fn test_mixed_clear_line_beginning_empty_line() {
 #[test]
 fn test_mixed_clear_from_cursor_to_end_of_screen() {
     let mut tty = VirtualTty::new(10, 4);
-    write!(tty.stdout, "Line1\nLine2\n").unwrap();
-    write!(tty.stderr, "Line3\nLine4").unwrap();
+    write!(tty.stdout, "Line1\r\nLine2\r\n").unwrap();
+    write!(tty.stderr, "Line3\r\nLine4").unwrap();
     write!(tty.stdout, "\x1b[2;3H").unwrap(); // Move to row 2, col 3
     write!(tty.stderr, "\x1b[0J").unwrap(); // Clear from cursor to end of screen
     let snapshot = tty.get_snapshot();
@@ -249,8 +251,8 @@ fn test_mixed_clear_from_cursor_to_end_of_screen() {
 #[test]
 fn test_mixed_clear_from_cursor_to_end_of_screen_default() {
     let mut tty = VirtualTty::new(10, 4);
-    write!(tty.stderr, "Line1\nLine2\n").unwrap();
-    write!(tty.stdout, "Line3\nLine4").unwrap();
+    write!(tty.stderr, "Line1\r\nLine2\r\n").unwrap();
+    write!(tty.stdout, "Line3\r\nLine4").unwrap();
     write!(tty.stderr, "\x1b[2;3H").unwrap(); // Move to row 2, col 3
     write!(tty.stdout, "\x1b[J").unwrap(); // Clear from cursor to end of screen (default)
     let snapshot = tty.get_snapshot();
@@ -265,8 +267,8 @@ fn test_mixed_clear_from_cursor_to_end_of_screen_default() {
 #[test]
 fn test_mixed_clear_from_cursor_at_start_of_line() {
     let mut tty = VirtualTty::new(8, 3);
-    write!(tty.stdout, "ABCD\n").unwrap();
-    write!(tty.stderr, "EFGH\n").unwrap();
+    write!(tty.stdout, "ABCD\r\n").unwrap();
+    write!(tty.stderr, "EFGH\r\n").unwrap();
     write!(tty.stdout, "IJKL").unwrap();
     write!(tty.stderr, "\x1b[2;1H").unwrap(); // Move to row 2, col 1
     write!(tty.stdout, "\x1b[0J").unwrap(); // Clear from cursor to end of screen
@@ -281,8 +283,8 @@ fn test_mixed_clear_from_cursor_at_start_of_line() {
 #[test]
 fn test_mixed_clear_from_cursor_preserves_position() {
     let mut tty = VirtualTty::new(10, 3);
-    write!(tty.stdout, "Hello\n").unwrap();
-    write!(tty.stderr, "World\n").unwrap();
+    write!(tty.stdout, "Hello\r\n").unwrap();
+    write!(tty.stderr, "World\r\n").unwrap();
     write!(tty.stdout, "Test").unwrap();
     write!(tty.stderr, "\x1b[2;2H").unwrap(); // Move to row 2, col 2
     write!(tty.stdout, "\x1b[0J").unwrap(); // Clear from cursor to end of screen
@@ -298,8 +300,8 @@ fn test_mixed_clear_from_cursor_preserves_position() {
 #[test]
 fn test_mixed_clear_from_cursor_on_last_line() {
     let mut tty = VirtualTty::new(10, 3);
-    write!(tty.stderr, "Line1\n").unwrap();
-    write!(tty.stdout, "Line2\n").unwrap();
+    write!(tty.stderr, "Line1\r\n").unwrap();
+    write!(tty.stdout, "Line2\r\n").unwrap();
     write!(tty.stderr, "Line3").unwrap();
     write!(tty.stdout, "\x1b[3D").unwrap(); // Move back 3 on last line
     write!(tty.stderr, "\x1b[0J").unwrap(); // Clear from cursor to end of screen
@@ -314,7 +316,7 @@ fn test_mixed_clear_from_cursor_on_last_line() {
 #[test]
 fn test_mixed_clear_from_cursor_with_subsequent_writes() {
     let mut tty = VirtualTty::new(12, 4);
-    write!(tty.stdout, "First\nSecond\nThird\nFourth").unwrap();
+    write!(tty.stdout, "First\r\nSecond\r\nThird\r\nFourth").unwrap();
     write!(tty.stderr, "\x1b[2;4H").unwrap(); // Move to row 2, col 4
     write!(tty.stdout, "\x1b[0J").unwrap(); // Clear from cursor to end of screen
     write!(tty.stderr, "NEW").unwrap();
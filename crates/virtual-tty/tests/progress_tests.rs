@@ -0,0 +1,64 @@
+use std::io::Write;
+use virtual_tty::{Progress, ProgressState, VirtualTty};
+
+// =============================================================================
+// OSC 9;4 PROGRESS
+// =============================================================================
+
+#[test]
+fn test_progress_is_cleared_by_default() {
+    let tty = VirtualTty::new(10, 1);
+    assert_eq!(
+        tty.get_progress(),
+        Progress {
+            state: ProgressState::Cleared,
+            percent: None,
+        }
+    );
+    assert!(tty.get_progress_history().is_empty());
+}
+
+#[test]
+fn test_progress_updates() {
+    let mut tty = VirtualTty::new(20, 2);
+    write!(tty.stdout, "\x1b]9;4;3\x07Downloading\r\n").unwrap();
+    for percent in [0, 50, 100] {
+        write!(tty.stdout, "\x1b]9;4;1;{percent}\x1b\\").unwrap();
+    }
+    write!(tty.stdout, "\x1b]9;4;0\x07Done").unwrap();
+
+    let progress = |state, percent| Progress { state, percent };
+    assert_eq!(
+        tty.get_progress_history(),
+        vec![
+            progress(ProgressState::Indeterminate, None),
+            progress(ProgressState::Normal, Some(0)),
+            progress(ProgressState::Normal, Some(50)),
+            progress(ProgressState::Normal, Some(100)),
+            progress(ProgressState::Cleared, None),
+        ]
+    );
+    assert_eq!(tty.get_progress().state, ProgressState::Cleared);
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    Downloading         \n
+    Done                \n
+    ");
+}
+
+#[test]
+fn test_progress_error_and_paused() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b]9;4;4;30\x07").unwrap();
+    assert_eq!(
+        tty.get_progress(),
+        Progress {
+            state: ProgressState::Paused,
+            percent: Some(30),
+        }
+    );
+    write!(tty.stdout, "\x1b]9;4;2;30\x07").unwrap();
+    assert_eq!(tty.get_progress().state, ProgressState::Error);
+    // Progress is not a notification
+    assert!(tty.get_notifications().is_empty());
+}
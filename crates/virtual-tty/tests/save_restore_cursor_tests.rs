@@ -0,0 +1,104 @@
+use std::io::Write;
+use virtual_tty::{Color, VirtualTty};
+
+// =============================================================================
+// DECSC / DECRC (ESC 7 / ESC 8)
+// =============================================================================
+
+#[test]
+fn test_save_restore_cursor_position() {
+    let mut tty = VirtualTty::new(20, 3);
+    write!(tty.stdout, "Progress: \x1b7").unwrap();
+    write!(tty.stdout, "10%\x1b8").unwrap();
+    write!(tty.stdout, "50%\x1b8").unwrap();
+    write!(tty.stdout, "100%").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    Progress: 100%      \n
+                        \n
+                        \n
+    ");
+}
+
+#[test]
+fn test_digits_after_esc_are_not_printed() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "A\x1b7B\x1b8C").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    AC        \n
+              \n
+    ");
+}
+
+#[test]
+fn test_restore_cursor_restores_pen() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "\x1b[31;1m\x1b7\x1b[0;32mA\x1b8B").unwrap();
+
+    let a = tty.cell_at(0, 0).unwrap();
+    // "B" overwrote "A" using the pen that was active when the cursor was saved
+    assert_eq!(a.ch, 'B');
+    assert_eq!(a.attributes.foreground, Color::RED);
+    assert!(a.attributes.bold);
+}
+
+#[test]
+fn test_restore_without_save_goes_home_with_default_pen() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "\x1b[2;5H\x1b[7m\x1b8X").unwrap();
+    assert_eq!(tty.get_cursor_position(), (0, 1));
+    assert!(tty.cell_at(0, 0).unwrap().attributes.is_default());
+}
+
+// =============================================================================
+// SCOSC / SCORC (CSI s / CSI u)
+// =============================================================================
+
+#[test]
+fn test_csi_save_restore_cursor() {
+    let mut tty = VirtualTty::new(10, 3);
+    write!(tty.stdout, "\x1b[2;3H\x1b[s\x1b[HTop\x1b[uX").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    Top       \n
+      X       \n
+              \n
+    ");
+}
+
+// =============================================================================
+// CHARACTER SETS
+// =============================================================================
+
+#[test]
+fn test_dec_line_drawing_charset() {
+    let mut tty = VirtualTty::new(6, 3);
+    write!(tty.stdout, "\x1b(0lqqk\r\nx  x\r\nmqqj\x1b(B").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    ┌──┐  \n
+    │  │  \n
+    └──┘  \n
+    ");
+}
+
+#[test]
+fn test_shift_out_uses_g1() {
+    let mut tty = VirtualTty::new(6, 1);
+    write!(tty.stdout, "\x1b)0q\x0eq\x0fq").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    q─q   \n
+    ");
+}
+
+#[test]
+fn test_restore_cursor_restores_charset() {
+    let mut tty = VirtualTty::new(6, 1);
+    write!(tty.stdout, "\x1b(0\x1b7\x1b(Bq\x1b8q").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    ─     \n
+    ");
+}
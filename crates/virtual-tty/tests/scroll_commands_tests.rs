@@ -0,0 +1,128 @@
+use std::io::Write;
+use virtual_tty::VirtualTty;
+
+// =============================================================================
+// SU / SD (CSI S / CSI T)
+// =============================================================================
+
+#[test]
+fn test_scroll_up() {
+    let mut tty = VirtualTty::new(6, 4);
+    write!(tty.stdout, "a\r\nb\r\nc\r\nd\x1b[2S").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    c     \n
+    d     \n
+          \n
+          \n
+    ");
+    // Scrolling does not move the cursor
+    assert_eq!(tty.get_cursor_position(), (3, 1));
+    assert_eq!(tty.get_scrollback_len(), 2);
+}
+
+#[test]
+fn test_scroll_down() {
+    let mut tty = VirtualTty::new(6, 4);
+    write!(tty.stdout, "a\r\nb\r\nc\r\nd\x1b[T").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+          \n
+    a     \n
+    b     \n
+    c     \n
+    ");
+}
+
+#[test]
+fn test_scroll_commands_respect_scroll_region() {
+    let mut tty = VirtualTty::new(6, 4);
+    write!(tty.stdout, "a\r\nb\r\nc\r\nd\x1b[2;3r\x1b[S").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    a     \n
+    c     \n
+          \n
+    d     \n
+    ");
+    write!(tty.stdout, "\x1b[2T").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    a     \n
+          \n
+          \n
+    d     \n
+    ");
+}
+
+// =============================================================================
+// IND / RI / NEL (ESC D / ESC M / ESC E)
+// =============================================================================
+
+#[test]
+fn test_index_keeps_column() {
+    let mut tty = VirtualTty::new(6, 3);
+    write!(tty.stdout, "ab\x1bDc").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    ab    \n
+      c   \n
+          \n
+    ");
+}
+
+#[test]
+fn test_index_scrolls_at_bottom() {
+    let mut tty = VirtualTty::new(6, 2);
+    write!(tty.stdout, "a\r\nb\x1bDc").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    b     \n
+     c    \n
+    ");
+}
+
+#[test]
+fn test_reverse_index_scrolls_at_top() {
+    let mut tty = VirtualTty::new(6, 3);
+    write!(tty.stdout, "line2\r\nline3\r\nline4").unwrap();
+    write!(tty.stdout, "\x1b[H\x1bMline1").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    line1 \n
+    line2 \n
+    line3 \n
+    ");
+}
+
+#[test]
+fn test_reverse_index_moves_up() {
+    let mut tty = VirtualTty::new(6, 3);
+    write!(tty.stdout, "\x1b[3;3H\x1bMx").unwrap();
+    assert_eq!(tty.get_cursor_position(), (1, 3));
+}
+
+#[test]
+fn test_reverse_index_at_top_margin_of_scroll_region() {
+    let mut tty = VirtualTty::new(6, 4);
+    write!(tty.stdout, "head\r\na\r\nb\r\nfoot").unwrap();
+    write!(tty.stdout, "\x1b[2;3r\x1b[2;1H\x1bMnew").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    head  \n
+    new   \n
+    a     \n
+    foot  \n
+    ");
+}
+
+#[test]
+fn test_next_line() {
+    let mut tty = VirtualTty::new(6, 2);
+    write!(tty.stdout, "ab\x1bEc\x1bEd").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    c     \n
+    d     \n
+    ");
+}
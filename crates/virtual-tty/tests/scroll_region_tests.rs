@@ -0,0 +1,101 @@
+use std::io::Write;
+use virtual_tty::VirtualTty;
+
+// =============================================================================
+// DECSTBM - SET TOP AND BOTTOM MARGINS
+// =============================================================================
+
+#[test]
+fn test_scroll_region_keeps_status_line_fixed() {
+    let mut tty = VirtualTty::new(10, 4);
+    write!(tty.stdout, "\x1b[4;1H-- INSERT").unwrap();
+    write!(tty.stdout, "\x1b[1;3r").unwrap();
+    write!(tty.stdout, "line1\r\nline2\r\nline3\r\nline4\r\nline5").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    line3     \n
+    line4     \n
+    line5     \n
+    -- INSERT \n
+    ");
+}
+
+#[test]
+fn test_scroll_region_in_the_middle_of_the_screen() {
+    let mut tty = VirtualTty::new(10, 5);
+    write!(tty.stdout, "header\r\n\r\n\r\n\r\nfooter").unwrap();
+    write!(tty.stdout, "\x1b[2;4r").unwrap();
+    write!(tty.stdout, "\x1b[4;1Ha\r\nb\r\nc").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    header    \n
+    a         \n
+    b         \n
+    c         \n
+    footer    \n
+    ");
+}
+
+#[test]
+fn test_scroll_region_moves_cursor_home() {
+    let mut tty = VirtualTty::new(10, 4);
+    write!(tty.stdout, "\x1b[3;5H").unwrap();
+    write!(tty.stdout, "\x1b[2;3r").unwrap();
+    assert_eq!(tty.get_cursor_position(), (0, 0));
+}
+
+#[test]
+fn test_line_feed_below_scroll_region_does_not_scroll() {
+    let mut tty = VirtualTty::new(10, 4);
+    write!(tty.stdout, "top\x1b[1;2r").unwrap();
+    write!(tty.stdout, "\x1b[4;1Hlast\r\nmore").unwrap();
+    assert_eq!(tty.get_cursor_position(), (3, 4));
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    top       \n
+              \n
+              \n
+    more      \n
+    ");
+}
+
+#[test]
+fn test_wrapping_at_bottom_margin_scrolls_region() {
+    let mut tty = VirtualTty::new(5, 3);
+    write!(tty.stdout, "\x1b[3;1Hfix\x1b[1;2r").unwrap();
+    write!(tty.stdout, "\x1b[2;1HabcdeXY").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    abcde\n
+    XY   \n
+    fix  \n
+    ");
+}
+
+#[test]
+fn test_reset_scroll_region() {
+    let mut tty = VirtualTty::new(10, 3);
+    write!(tty.stdout, "\x1b[1;2r\x1b[r").unwrap();
+    write!(tty.stdout, "a\r\nb\r\nc\r\nd").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    b         \n
+    c         \n
+    d         \n
+    ");
+}
+
+#[test]
+fn test_invalid_scroll_region_is_ignored() {
+    let mut tty = VirtualTty::new(10, 3);
+    write!(tty.stdout, "\x1b[2;2H\x1b[3;2r").unwrap();
+    // The cursor is not homed because the sequence was rejected
+    assert_eq!(tty.get_cursor_position(), (1, 1));
+    write!(tty.stdout, "\x1b[3;1Ha\r\nb").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+              \n
+    a         \n
+    b         \n
+    ");
+}
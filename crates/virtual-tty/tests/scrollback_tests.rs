@@ -0,0 +1,130 @@
+use std::io::Write;
+use virtual_tty::{VirtualTty, DEFAULT_SCROLLBACK_LIMIT};
+
+// =============================================================================
+// LINES SCROLLED OFF THE SCREEN
+// =============================================================================
+
+#[test]
+fn test_scrolled_lines_are_kept_in_scrollback() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "Line1\r\nLine2\r\nLine3\r\nLine4").unwrap();
+    assert_eq!(tty.get_scrollback_len(), 2);
+    let scrollback = tty.get_scrollback_snapshot();
+    insta::assert_snapshot!(scrollback, @r"
+    Line1     \n
+    Line2     \n
+    ");
+}
+
+#[test]
+fn test_history_contains_scrollback_and_screen() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "Line1\r\nLine2\r\nLine3").unwrap();
+    let history = tty.get_history_snapshot();
+    insta::assert_snapshot!(history, @r"
+    Line1     \n
+    Line2     \n
+    Line3     \n
+    ");
+}
+
+#[test]
+fn test_scrollback_is_empty_without_scrolling() {
+    let mut tty = VirtualTty::new(10, 3);
+    write!(tty.stdout, "Line1\r\nLine2").unwrap();
+    assert_eq!(tty.get_scrollback_len(), 0);
+    assert_eq!(tty.get_history_snapshot(), tty.get_snapshot());
+}
+
+// =============================================================================
+// LIMITS
+// =============================================================================
+
+#[test]
+fn test_scrollback_limit_drops_oldest_lines() {
+    let mut tty = VirtualTty::with_scrollback_limit(10, 2, 2);
+    for i in 1..=6 {
+        write!(tty.stdout, "\r\nLine{i}").unwrap();
+    }
+    let scrollback = tty.get_scrollback_snapshot();
+    insta::assert_snapshot!(scrollback, @r"
+    Line3     \n
+    Line4     \n
+    ");
+}
+
+#[test]
+fn test_zero_scrollback_limit_disables_scrollback() {
+    let mut tty = VirtualTty::with_scrollback_limit(10, 2, 0);
+    write!(tty.stdout, "Line1\r\nLine2\r\nLine3").unwrap();
+    assert_eq!(tty.get_scrollback_len(), 0);
+}
+
+#[test]
+fn test_lowering_scrollback_limit_truncates() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "a\r\nb\r\nc\r\nd").unwrap();
+    assert_eq!(tty.get_scrollback_len(), 3);
+    tty.set_scrollback_limit(1);
+    let scrollback = tty.get_scrollback_snapshot();
+    insta::assert_snapshot!(scrollback, @r"
+    c         \n
+    ");
+}
+
+#[test]
+fn test_default_scrollback_limit() {
+    let mut tty = VirtualTty::new(10, 1);
+    for _ in 0..DEFAULT_SCROLLBACK_LIMIT + 10 {
+        writeln!(tty.stdout, "x").unwrap();
+    }
+    assert_eq!(tty.get_scrollback_len(), DEFAULT_SCROLLBACK_LIMIT);
+}
+
+// =============================================================================
+// CLEARING AND SPECIAL CASES
+// =============================================================================
+
+#[test]
+fn test_clear_scrollback_sequence() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "Line1\r\nLine2\r\nLine3").unwrap();
+    write!(tty.stdout, "\x1b[3J").unwrap();
+    assert_eq!(tty.get_scrollback_len(), 0);
+    // The screen itself is left untouched
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    Line2     \n
+    Line3     \n
+    ");
+}
+
+#[test]
+fn test_clear_scrollback_api() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "Line1\r\nLine2\r\nLine3").unwrap();
+    tty.clear_scrollback();
+    assert_eq!(tty.get_scrollback_len(), 0);
+}
+
+#[test]
+fn test_scroll_region_below_top_does_not_feed_scrollback() {
+    let mut tty = VirtualTty::new(10, 3);
+    write!(tty.stdout, "\x1b[2;3r\x1b[3;1Ha\r\nb\r\nc").unwrap();
+    assert_eq!(tty.get_scrollback_len(), 0);
+}
+
+#[test]
+fn test_alternate_screen_does_not_feed_scrollback() {
+    let mut tty = VirtualTty::new(10, 2);
+    writeln!(tty.stdout, "shell").unwrap();
+    write!(tty.stdout, "\x1b[?1049ha\r\nb\r\nc\r\nd").unwrap();
+    assert_eq!(tty.get_scrollback_len(), 0);
+    write!(tty.stdout, "\x1b[?1049l").unwrap();
+    let history = tty.get_history_snapshot();
+    insta::assert_snapshot!(history, @r"
+    shell     \n
+              \n
+    ");
+}
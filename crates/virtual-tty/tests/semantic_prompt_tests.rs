@@ -0,0 +1,217 @@
+use std::io::Write;
+use virtual_tty::{SemanticCommand, VirtualTty, VirtualTtyStreams};
+
+const PROMPT_START: &str = "\x1b]133;A\x07";
+const COMMAND_START: &str = "\x1b]133;B\x07";
+const OUTPUT_START: &str = "\x1b]133;C\x07";
+
+/// Writes what a shell with OSC 133 integration prints around a command
+fn run(tty: &mut VirtualTtyStreams, command: &str, output: &str, exit_code: i32) {
+    write!(
+        tty.stdout,
+        "{PROMPT_START}$ {COMMAND_START}{command}\r\n{OUTPUT_START}{output}\x1b]133;D;{exit_code}\x07"
+    )
+    .unwrap();
+}
+
+// =============================================================================
+// COMMANDS
+// =============================================================================
+
+#[test]
+fn test_commands_with_output_and_exit_code() {
+    let mut tty = VirtualTty::new(20, 10);
+    run(&mut tty, "echo hi", "hi\r\n", 0);
+    run(&mut tty, "ls nope", "ls: nope: missing\r\n", 2);
+    assert_eq!(
+        tty.get_commands(),
+        vec![
+            SemanticCommand {
+                prompt: "$".to_string(),
+                command: "echo hi".to_string(),
+                output: "hi".to_string(),
+                exit_code: Some(0),
+                finished: true,
+            },
+            SemanticCommand {
+                prompt: "$".to_string(),
+                command: "ls nope".to_string(),
+                output: "ls: nope: missing".to_string(),
+                exit_code: Some(2),
+                finished: true,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_multiline_output() {
+    let mut tty = VirtualTty::new(20, 10);
+    run(&mut tty, "seq 3", "1\r\n2\r\n\r\n3\r\n", 0);
+    let last = tty.get_last_command().unwrap();
+    assert_eq!(last.output, "1\n2\n\n3");
+}
+
+#[test]
+fn test_command_being_typed() {
+    let mut tty = VirtualTty::new(20, 5);
+    run(&mut tty, "true", "", 0);
+    write!(tty.stdout, "{PROMPT_START}~ $ {COMMAND_START}git st").unwrap();
+    let last = tty.get_last_command().unwrap();
+    assert_eq!(last.prompt, "~ $");
+    assert_eq!(last.command, "git st");
+    assert_eq!(last.output, "");
+    assert!(!last.finished);
+    // The command without output has an empty output
+    assert_eq!(tty.get_commands()[0].output, "");
+}
+
+#[test]
+fn test_next_prompt_ends_command_without_exit_mark() {
+    let mut tty = VirtualTty::new(20, 5);
+    write!(
+        tty.stdout,
+        "{PROMPT_START}> {COMMAND_START}1+1\r\n{OUTPUT_START}2\r\n"
+    )
+    .unwrap();
+    write!(tty.stdout, "{PROMPT_START}> ").unwrap();
+    let commands = tty.get_commands();
+    assert_eq!(commands.len(), 2);
+    assert_eq!(commands[0].output, "2");
+    assert!(commands[0].finished);
+    assert_eq!(commands[0].exit_code, None);
+}
+
+#[test]
+fn test_marks_without_prompt_are_ignored() {
+    let mut tty = VirtualTty::new(20, 5);
+    write!(tty.stdout, "\x1b]133;D;0\x07\x1b]133;C\x07text").unwrap();
+    assert!(tty.get_commands().is_empty());
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    text                \n
+                        \n
+                        \n
+                        \n
+                        \n
+    ");
+}
+
+// =============================================================================
+// SCROLLBACK
+// =============================================================================
+
+#[test]
+fn test_output_scrolled_into_scrollback() {
+    let mut tty = VirtualTty::new(20, 4);
+    let output: String = (1..=6).map(|n| format!("line {n}\r\n")).collect();
+    run(&mut tty, "cat log", &output, 0);
+    write!(tty.stdout, "{PROMPT_START}$ ").unwrap();
+    assert!(tty.get_scrollback_len() > 0);
+    let commands = tty.get_commands();
+    assert_eq!(commands[0].command, "cat log");
+    assert_eq!(
+        commands[0].output,
+        "line 1\nline 2\nline 3\nline 4\nline 5\nline 6"
+    );
+}
+
+#[test]
+fn test_output_dropped_from_scrollback() {
+    let mut tty = VirtualTty::new(20, 3);
+    tty.set_scrollback_limit(2);
+    let output: String = (1..=6).map(|n| format!("line {n}\r\n")).collect();
+    run(&mut tty, "cat log", &output, 0);
+    // The command was captured when the output started, but only the output
+    // lines still in the scrollback or on screen are returned
+    let last = tty.get_last_command().unwrap();
+    assert_eq!(last.command, "cat log");
+    assert_eq!(last.output, "line 3\nline 4\nline 5\nline 6");
+}
+
+#[test]
+fn test_commands_survive_clearing_the_screen() {
+    let mut tty = VirtualTty::new(20, 5);
+    run(&mut tty, "ls", "a.txt b.txt\r\n", 0);
+    write!(
+        tty.stdout,
+        "{PROMPT_START}$ {COMMAND_START}clear\r\n{OUTPUT_START}"
+    )
+    .unwrap();
+    write!(tty.stdout, "\x1b[2J\x1b[H\x1b]133;D;0\x07{PROMPT_START}$ ").unwrap();
+    let commands = tty.get_commands();
+    assert_eq!(
+        commands[0],
+        SemanticCommand {
+            prompt: "$".to_string(),
+            command: "ls".to_string(),
+            output: "a.txt b.txt".to_string(),
+            exit_code: Some(0),
+            finished: true,
+        }
+    );
+    assert_eq!(commands[1].command, "clear");
+    assert_eq!(commands[1].output, "");
+    assert_eq!(commands[2].prompt, "$");
+}
+
+#[test]
+fn test_output_continues_after_clearing_the_screen() {
+    let mut tty = VirtualTty::new(20, 5);
+    write!(
+        tty.stdout,
+        "{PROMPT_START}$ {COMMAND_START}watch\r\n{OUTPUT_START}out"
+    )
+    .unwrap();
+    write!(tty.stdout, "\x1b[2J\x1b[Hmore").unwrap();
+    assert_eq!(tty.get_last_command().unwrap().output, "out\nmore");
+    write!(tty.stdout, "\x1b]133;D\x07").unwrap();
+    let last = tty.get_last_command().unwrap();
+    assert_eq!(last.command, "watch");
+    assert_eq!(last.output, "out\nmore");
+    assert!(last.finished);
+}
+
+#[test]
+fn test_output_survives_clearing_the_scrollback() {
+    let mut tty = VirtualTty::new(20, 3);
+    let output: String = (1..=4).map(|n| format!("line {n}\r\n")).collect();
+    write!(
+        tty.stdout,
+        "{PROMPT_START}$ {COMMAND_START}cat log\r\n{OUTPUT_START}{output}"
+    )
+    .unwrap();
+    write!(tty.stdout, "\x1b[3Jdone\x1b]133;D;0\x07").unwrap();
+    assert_eq!(tty.get_scrollback_len(), 0);
+    let last = tty.get_last_command().unwrap();
+    assert_eq!(last.command, "cat log");
+    assert_eq!(last.output, "line 1\nline 2\nline 3\nline 4\ndone");
+}
+
+#[test]
+fn test_commands_survive_scrolling_a_region() {
+    let mut tty = VirtualTty::new(20, 5);
+    run(&mut tty, "echo hi", "hi\r\n", 0);
+    // Inserting lines above the command and scrolling the region below it
+    // moves the text without changing what was captured
+    write!(tty.stdout, "\x1b[1;1H\x1b[2L\x1b[2;5r\x1b[5;1H\n\n\x1b[r").unwrap();
+    let last = tty.get_last_command().unwrap();
+    assert_eq!(last.command, "echo hi");
+    assert_eq!(last.output, "hi");
+}
+
+#[test]
+fn test_full_screen_program_output_is_not_captured() {
+    let mut tty = VirtualTty::new(20, 4);
+    write!(
+        tty.stdout,
+        "{PROMPT_START}$ {COMMAND_START}vim\r\n{OUTPUT_START}"
+    )
+    .unwrap();
+    write!(tty.stdout, "\x1b[?1049h~ editing\x1b]133;A\x07").unwrap();
+    write!(tty.stdout, "\x1b[?1049l\x1b]133;D;0\x07").unwrap();
+    let commands = tty.get_commands();
+    assert_eq!(commands.len(), 1);
+    assert_eq!(commands[0].command, "vim");
+    assert_eq!(commands[0].output, "");
+}
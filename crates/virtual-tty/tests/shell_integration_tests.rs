@@ -0,0 +1,74 @@
+use std::io::Write;
+use virtual_tty::{Notification, VirtualTty};
+
+// =============================================================================
+// OSC 7 WORKING DIRECTORY
+// =============================================================================
+
+#[test]
+fn test_working_directory() {
+    let mut tty = VirtualTty::new(10, 1);
+    assert_eq!(tty.get_working_directory(), None);
+    write!(tty.stdout, "\x1b]7;file://laptop/home/me\x1b\\$ ").unwrap();
+    assert_eq!(tty.get_working_directory(), Some("/home/me".to_string()));
+    write!(tty.stdout, "\x1b]7;file:///tmp/with%20space\x07").unwrap();
+    assert_eq!(
+        tty.get_working_directory(),
+        Some("/tmp/with space".to_string())
+    );
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @"$         \\n");
+}
+
+#[test]
+fn test_working_directory_ignores_other_urls() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b]7;file:///src\x07\x1b]7;https://x.io/\x07").unwrap();
+    assert_eq!(tty.get_working_directory(), Some("/src".to_string()));
+}
+
+// =============================================================================
+// OSC 9 / OSC 777 NOTIFICATIONS
+// =============================================================================
+
+#[test]
+fn test_notifications() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "building\r\n\x1b]9;Build finished\x07").unwrap();
+    write!(tty.stdout, "\x1b]777;notify;cargo;2 warnings\x1b\\").unwrap();
+    assert_eq!(
+        tty.get_notifications(),
+        vec![
+            Notification {
+                title: String::new(),
+                body: "Build finished".to_string(),
+            },
+            Notification {
+                title: "cargo".to_string(),
+                body: "2 warnings".to_string(),
+            },
+        ]
+    );
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    building  \n
+              \n
+    ");
+}
+
+#[test]
+fn test_notifications_survive_reset() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b]9;one\x07\x1b]7;file:///a\x07\x1bc").unwrap();
+    assert_eq!(tty.get_notifications().len(), 1);
+    assert_eq!(tty.get_working_directory(), Some("/a".to_string()));
+}
+
+#[test]
+fn test_unknown_osc_777_command_is_ignored() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b]777;preexec\x07ok").unwrap();
+    assert!(tty.get_notifications().is_empty());
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @"ok        \\n");
+}
@@ -8,7 +8,7 @@ use virtual_tty::VirtualTty;
 #[test]
 fn test_stderr_complex_cursor_sequence() {
     let mut tty = VirtualTty::new(10, 5);
-    write!(tty.stderr, "Hello\nWorld\nTest").unwrap();
+    write!(tty.stderr, "Hello\r\nWorld\r\nTest").unwrap();
     write!(tty.stderr, "\x1b[2A").unwrap(); // Up 2 lines
     write!(tty.stderr, "\x1b[2C").unwrap(); // Right 2 columns
     write!(tty.stderr, "X").unwrap();
@@ -9,7 +9,7 @@ use virtual_tty::VirtualTty;
 #[test]
 fn test_stderr_cursor_up_basic() {
     let mut tty = VirtualTty::new(10, 5);
-    write!(tty.stderr, "Line1\nLine2\nLine3").unwrap();
+    write!(tty.stderr, "Line1\r\nLine2\r\nLine3").unwrap();
     write!(tty.stderr, "\x1b[1A").unwrap(); // Move up 1 line
     write!(tty.stderr, "X").unwrap();
     let snapshot = tty.get_snapshot();
@@ -25,7 +25,7 @@ fn test_stderr_cursor_up_basic() {
 #[test]
 fn test_stderr_cursor_up_multiple() {
     let mut tty = VirtualTty::new(10, 5);
-    write!(tty.stderr, "Line1\nLine2\nLine3").unwrap();
+    write!(tty.stderr, "Line1\r\nLine2\r\nLine3").unwrap();
     write!(tty.stderr, "\x1b[2A").unwrap(); // Move up 2 lines
     write!(tty.stderr, "X").unwrap();
     let snapshot = tty.get_snapshot();
@@ -41,7 +41,7 @@ fn test_stderr_cursor_up_multiple() {
 #[test]
 fn test_stderr_cursor_up_no_parameter() {
     let mut tty = VirtualTty::new(10, 5);
-    write!(tty.stderr, "Line1\nLine2").unwrap();
+    write!(tty.stderr, "Line1\r\nLine2").unwrap();
     write!(tty.stderr, "\x1b[A").unwrap(); // Move up 1 line (default)
     write!(tty.stderr, "X").unwrap();
     let snapshot = tty.get_snapshot();
@@ -270,7 +270,7 @@ fn test_stderr_absolute_cursor_positioning() {
 #[test]
 fn test_stderr_set_cursor_to_row_col() {
     let mut tty = VirtualTty::new(10, 5);
-    write!(tty.stderr, "Hello\nWorld").unwrap();
+    write!(tty.stderr, "Hello\r\nWorld").unwrap();
     write!(tty.stderr, "\x1b[2;3H").unwrap(); // Move to row 2, col 3
     write!(tty.stderr, "X").unwrap();
     let snapshot = tty.get_snapshot();
@@ -286,7 +286,7 @@ fn test_stderr_set_cursor_to_row_col() {
 #[test]
 fn test_stderr_set_cursor_to_row_col_alt_syntax() {
     let mut tty = VirtualTty::new(10, 5);
-    write!(tty.stderr, "Hello\nWorld").unwrap();
+    write!(tty.stderr, "Hello\r\nWorld").unwrap();
     write!(tty.stderr, "\x1b[2;3f").unwrap(); // Move to row 2, col 3 (f command)
     write!(tty.stderr, "X").unwrap();
     let snapshot = tty.get_snapshot();
@@ -302,7 +302,7 @@ fn test_stderr_set_cursor_to_row_col_alt_syntax() {
 #[test]
 fn test_stderr_set_cursor_to_home_position() {
     let mut tty = VirtualTty::new(10, 5);
-    write!(tty.stderr, "Hello\nWorld").unwrap();
+    write!(tty.stderr, "Hello\r\nWorld").unwrap();
     write!(tty.stderr, "\x1b[H").unwrap(); // Move to row 1, col 1 (default)
     write!(tty.stderr, "X").unwrap();
     let snapshot = tty.get_snapshot();
@@ -318,7 +318,7 @@ fn test_stderr_set_cursor_to_home_position() {
 #[test]
 fn test_stderr_set_cursor_partial_coordinates() {
     let mut tty = VirtualTty::new(10, 5);
-    write!(tty.stderr, "Hello\nWorld").unwrap();
+    write!(tty.stderr, "Hello\r\nWorld").unwrap();
     write!(tty.stderr, "\x1b[2;H").unwrap(); // Move to row 2, col 1 (default)
     write!(tty.stderr, "X").unwrap();
     let snapshot = tty.get_snapshot();
@@ -334,11 +334,12 @@ fn test_stderr_set_cursor_partial_coordinates() {
 #[test]
 fn test_stderr_set_cursor_bounds_clamping() {
     let mut tty = VirtualTty::new(15, 10);
-    write!(tty.stderr, "Hello\nWorld").unwrap();
+    write!(tty.stderr, "Hello\r\nWorld").unwrap();
     write!(tty.stderr, "\x1b[20;30H").unwrap(); // Try to move to row 20, col 30 (should be clamped)
     write!(tty.stderr, "X").unwrap();
     let snapshot = tty.get_snapshot();
     insta::assert_snapshot!(snapshot, @r"
+    Hello          \n
     World          \n
                    \n
                    \n
@@ -348,6 +349,5 @@ fn test_stderr_set_cursor_bounds_clamping() {
                    \n
                    \n
                   X\n
-                   \n
     ");
 }
@@ -23,7 +23,7 @@ fn test_stderr_clear_line_from_cursor() {
 #[test]
 fn test_stderr_clear_entire_line() {
     let mut tty = VirtualTty::new(10, 3);
-    write!(tty.stderr, "Hello\nWorld\nTest").unwrap();
+    write!(tty.stderr, "Hello\r\nWorld\r\nTest").unwrap();
     write!(tty.stderr, "\x1b[2A").unwrap(); // Move up 2 lines
     write!(tty.stderr, "\x1b[2K").unwrap(); // Clear entire line
     write!(tty.stderr, "New").unwrap();
@@ -38,7 +38,7 @@ fn test_stderr_clear_entire_line() {
 #[test]
 fn test_stderr_clear_from_cursor_to_end_of_screen() {
     let mut tty = VirtualTty::new(10, 4);
-    write!(tty.stderr, "Line1\nLine2\nLine3\nLine4").unwrap();
+    write!(tty.stderr, "Line1\r\nLine2\r\nLine3\r\nLine4").unwrap();
     write!(tty.stderr, "\x1b[2;3H").unwrap(); // Move to row 2, col 3
     write!(tty.stderr, "\x1b[0J").unwrap(); // Clear from cursor to end of screen
     let snapshot = tty.get_snapshot();
@@ -53,7 +53,7 @@ fn test_stderr_clear_from_cursor_to_end_of_screen() {
 #[test]
 fn test_stderr_clear_from_cursor_to_end_of_screen_default() {
     let mut tty = VirtualTty::new(10, 4);
-    write!(tty.stderr, "Line1\nLine2\nLine3\nLine4").unwrap();
+    write!(tty.stderr, "Line1\r\nLine2\r\nLine3\r\nLine4").unwrap();
     write!(tty.stderr, "\x1b[2;3H").unwrap(); // Move to row 2, col 3
     write!(tty.stderr, "\x1b[J").unwrap(); // Clear from cursor to end of screen (default)
     let snapshot = tty.get_snapshot();
@@ -68,7 +68,7 @@ fn test_stderr_clear_from_cursor_to_end_of_screen_default() {
 #[test]
 fn test_stderr_clear_from_cursor_at_start_of_line() {
     let mut tty = VirtualTty::new(8, 3);
-    write!(tty.stderr, "ABCD\nEFGH\nIJKL").unwrap();
+    write!(tty.stderr, "ABCD\r\nEFGH\r\nIJKL").unwrap();
     write!(tty.stderr, "\x1b[2;1H").unwrap(); // Move to row 2, col 1
     write!(tty.stderr, "\x1b[0J").unwrap(); // Clear from cursor to end of screen
     let snapshot = tty.get_snapshot();
@@ -82,7 +82,7 @@ fn test_stderr_clear_from_cursor_at_start_of_line() {
 #[test]
 fn test_stderr_clear_from_cursor_at_end_of_line() {
     let mut tty = VirtualTty::new(8, 3);
-    write!(tty.stderr, "ABCD\nEFGH\nIJKL").unwrap();
+    write!(tty.stderr, "ABCD\r\nEFGH\r\nIJKL").unwrap();
     write!(tty.stderr, "\x1b[1;4H").unwrap(); // Move to row 1, col 4 (end of first line)
     write!(tty.stderr, "\x1b[0J").unwrap(); // Clear from cursor to end of screen
     let snapshot = tty.get_snapshot();
@@ -96,7 +96,7 @@ fn test_stderr_clear_from_cursor_at_end_of_line() {
 #[test]
 fn test_stderr_clear_from_cursor_preserves_position() {
     let mut tty = VirtualTty::new(10, 3);
-    write!(tty.stderr, "Hello\nWorld\nTest").unwrap();
+    write!(tty.stderr, "Hello\r\nWorld\r\nTest").unwrap();
     write!(tty.stderr, "\x1b[2;2H").unwrap(); // Move to row 2, col 2
     write!(tty.stderr, "\x1b[0J").unwrap(); // Clear from cursor to end of screen
     write!(tty.stderr, "X").unwrap(); // Should write at cursor position
@@ -111,7 +111,7 @@ fn test_stderr_clear_from_cursor_preserves_position() {
 #[test]
 fn test_stderr_clear_from_cursor_on_last_line() {
     let mut tty = VirtualTty::new(10, 3);
-    write!(tty.stderr, "Line1\nLine2\nLine3").unwrap();
+    write!(tty.stderr, "Line1\r\nLine2\r\nLine3").unwrap();
     write!(tty.stderr, "\x1b[3D").unwrap(); // Move back 3 on last line
     write!(tty.stderr, "\x1b[0J").unwrap(); // Clear from cursor to end of screen
     let snapshot = tty.get_snapshot();
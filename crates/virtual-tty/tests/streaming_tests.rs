@@ -0,0 +1,61 @@
+use std::io::Write;
+use virtual_tty::{Color, VirtualTty};
+
+// =============================================================================
+// SEQUENCES SPLIT ACROSS WRITES
+// =============================================================================
+
+#[test]
+fn test_csi_sequence_split_across_writes() {
+    let mut tty = VirtualTty::new(10, 3);
+    write!(tty.stdout, "Hello\x1b[").unwrap();
+    write!(tty.stdout, "2;").unwrap();
+    write!(tty.stdout, "3HWorld").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    Hello     \n
+      World   \n
+              \n
+    ");
+}
+
+#[test]
+fn test_escape_split_after_esc() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "A\x1b").unwrap();
+    write!(tty.stdout, "[31mB").unwrap();
+    assert_eq!(tty.cell_at(0, 1).unwrap().attributes.foreground, Color::RED);
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @"AB        \\n");
+}
+
+#[test]
+fn test_every_byte_written_separately() {
+    let mut tty = VirtualTty::new(10, 2);
+    for ch in "ab\x1b[2;1H\x1b(0q\x1b(Bc".chars() {
+        write!(tty.stdout, "{ch}").unwrap();
+    }
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    ab        \n
+    ─c        \n
+    ");
+}
+
+#[test]
+fn test_sequence_continued_on_other_stream() {
+    // Both streams feed the same terminal, like a real tty
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b[1").unwrap();
+    write!(tty.stderr, "mB").unwrap();
+    assert!(tty.cell_at(0, 0).unwrap().attributes.bold);
+}
+
+#[test]
+fn test_send_input_keeps_parser_state() {
+    let mut tty = VirtualTty::new(10, 1);
+    tty.send_input("x\x1b[");
+    tty.send_input("5GY");
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @"x   Y     \\n");
+}
@@ -0,0 +1,59 @@
+use std::io::Write;
+use virtual_tty::VirtualTty;
+
+// =============================================================================
+// STYLE RUN ANNOTATIONS
+// =============================================================================
+
+#[test]
+fn test_styled_snapshot_plain_text_matches_snapshot() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "Hello\nWorld").unwrap();
+    assert_eq!(tty.get_styled_snapshot(), tty.get_snapshot());
+}
+
+#[test]
+fn test_styled_snapshot_marks_style_runs() {
+    let mut tty = VirtualTty::new(15, 2);
+    write!(tty.stderr, "\x1b[1;31mError\x1b[0m: missing").unwrap();
+    let snapshot = tty.get_styled_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    [fg=red,bold]Error[/]: missing \n
+                   \n
+    ");
+}
+
+#[test]
+fn test_styled_snapshot_only_marks_changes() {
+    let mut tty = VirtualTty::new(12, 1);
+    write!(tty.stdout, "\x1b[32mok\x1b[32m ok\x1b[4m!\x1b[24m?\x1b[m").unwrap();
+    let snapshot = tty.get_styled_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    [fg=green]ok ok[fg=green,underline]![fg=green]?[/]     \n
+    ");
+}
+
+#[test]
+fn test_styled_snapshot_closes_runs_at_line_end() {
+    let mut tty = VirtualTty::new(6, 2);
+    write!(tty.stdout, "\x1b[7mselect\x1b[0m").unwrap();
+    let snapshot = tty.get_styled_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    [reverse]select[/]\n
+          \n
+    ");
+}
+
+#[test]
+fn test_styled_snapshot_extended_colors() {
+    let mut tty = VirtualTty::new(8, 1);
+    write!(
+        tty.stdout,
+        "\x1b[92mA\x1b[38;5;208mB\x1b[38;2;255;0;128;48;5;4mC\x1b[0m"
+    )
+    .unwrap();
+    let snapshot = tty.get_styled_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    [fg=bright-green]A[fg=208]B[fg=#ff0080,bg=blue]C[/]     \n
+    ");
+}
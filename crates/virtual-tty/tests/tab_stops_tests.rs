@@ -0,0 +1,165 @@
+use std::io::Write;
+use virtual_tty::VirtualTty;
+
+// =============================================================================
+// DEFAULT TAB STOPS
+// =============================================================================
+
+#[test]
+fn test_default_tab_stops() {
+    let tty = VirtualTty::new(20, 2);
+    assert_eq!(tty.get_tab_stops(), vec![8, 16]);
+}
+
+#[test]
+fn test_tab_moves_without_overwriting() {
+    let mut tty = VirtualTty::new(20, 2);
+    write!(tty.stdout, "abcdefghijkl\r\tX").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    abcdefghXjkl        \n
+                        \n
+    ");
+}
+
+#[test]
+fn test_tab_stops_at_last_column() {
+    let mut tty = VirtualTty::new(20, 2);
+    write!(tty.stdout, "a\t\t\t").unwrap();
+    assert_eq!(tty.get_cursor_position(), (0, 19));
+}
+
+// =============================================================================
+// HTS / TBC (ESC H / CSI g)
+// =============================================================================
+
+#[test]
+fn test_set_tab_stop() {
+    let mut tty = VirtualTty::new(20, 2);
+    write!(tty.stdout, "\x1b[4G\x1bH\x1b[1G").unwrap();
+    assert_eq!(tty.get_tab_stops(), vec![3, 8, 16]);
+    write!(tty.stdout, "a\tb\tc").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    a  b    c           \n
+                        \n
+    ");
+}
+
+#[test]
+fn test_clear_tab_stop_at_cursor() {
+    let mut tty = VirtualTty::new(20, 2);
+    write!(tty.stdout, "\x1b[9G\x1b[g\x1b[1G\tX").unwrap();
+    assert_eq!(tty.get_tab_stops(), vec![16]);
+    assert_eq!(tty.get_cursor_position(), (0, 17));
+}
+
+#[test]
+fn test_clear_all_tab_stops() {
+    let mut tty = VirtualTty::new(20, 2);
+    write!(tty.stdout, "\x1b[3g").unwrap();
+    assert!(tty.get_tab_stops().is_empty());
+    write!(tty.stdout, "\t").unwrap();
+    assert_eq!(tty.get_cursor_position(), (0, 19));
+}
+
+// =============================================================================
+// CHT / CBT (CSI I / CSI Z)
+// =============================================================================
+
+#[test]
+fn test_forward_tab() {
+    let mut tty = VirtualTty::new(20, 2);
+    write!(tty.stdout, "\x1b[IA\x1b[1G\x1b[2IB").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+            A       B   \n
+                        \n
+    ");
+}
+
+#[test]
+fn test_back_tab() {
+    let mut tty = VirtualTty::new(20, 2);
+    write!(tty.stdout, "\x1b[19G\x1b[Z").unwrap();
+    assert_eq!(tty.get_cursor_position(), (0, 16));
+    write!(tty.stdout, "\x1b[Z").unwrap();
+    assert_eq!(tty.get_cursor_position(), (0, 8));
+    // Stops at the first column when there are not enough tab stops
+    write!(tty.stdout, "\x1b[5Z").unwrap();
+    assert_eq!(tty.get_cursor_position(), (0, 0));
+}
+
+// =============================================================================
+// RESET AND RESIZE
+// =============================================================================
+
+#[test]
+fn test_reset_restores_default_tab_stops() {
+    let mut tty = VirtualTty::new(20, 2);
+    write!(tty.stdout, "\x1b[3g\x1b[5G\x1bHtext\x1bc").unwrap();
+    assert_eq!(tty.get_tab_stops(), vec![8, 16]);
+    assert_eq!(tty.get_cursor_position(), (0, 0));
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+                        \n
+                        \n
+    ");
+}
+
+#[test]
+fn test_resize_resets_tab_stops() {
+    let mut tty = VirtualTty::new(20, 2);
+    write!(tty.stdout, "\x1b[3g\x1b[5G\x1bH").unwrap();
+    assert_eq!(tty.get_tab_stops(), vec![4]);
+    tty.resize(30, 3);
+    assert_eq!(tty.get_size(), (30, 3));
+    assert_eq!(tty.get_tab_stops(), vec![8, 16, 24]);
+}
+
+#[test]
+fn test_resize_keeps_content() {
+    let mut tty = VirtualTty::new(10, 3);
+    write!(tty.stdout, "Hello\r\nWorld\r\nBye").unwrap();
+    tty.resize(4, 2);
+    assert_eq!(tty.get_cursor_position(), (1, 3));
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    Hell\n
+    Worl\n
+    ");
+}
+
+#[test]
+fn test_resize_to_zero_and_back() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "Hello").unwrap();
+    tty.resize(0, 0);
+    assert_eq!(tty.get_size(), (0, 0));
+    assert_eq!(tty.get_cursor_position(), (0, 0));
+    // Nothing can be shown, but writing must not fail
+    write!(
+        tty.stdout,
+        "lost\r\nmore\n\x1b[2J\x1b[3@\x1b[2M\x1bM\x1b[5X\t"
+    )
+    .unwrap();
+    tty.resize(3, 0);
+    write!(tty.stdout, "abc\n\x1b[L").unwrap();
+    tty.resize(0, 2);
+    write!(tty.stdout, "abc\n\x1b[K\x1b[P").unwrap();
+    tty.resize(6, 2);
+    write!(tty.stdout, "\x1b[Hok").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    ok    \n
+          \n
+    ");
+}
+
+#[test]
+fn test_zero_size_screen_accepts_output() {
+    let mut tty = VirtualTty::new(0, 0);
+    write!(tty.stdout, "text\n\x1b[2J").unwrap();
+    assert_eq!(tty.get_cursor_position(), (0, 0));
+    assert_eq!(tty.get_snapshot(), "\n");
+}
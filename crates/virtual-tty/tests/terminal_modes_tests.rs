@@ -0,0 +1,85 @@
+use std::io::Write;
+use virtual_tty::VirtualTty;
+
+// =============================================================================
+// IRM (CSI 4 h / CSI 4 l)
+// =============================================================================
+
+#[test]
+fn test_insert_mode_shifts_line_right() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "Hello\x1b[1G\x1b[4h>> ").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    >> Hello  \n
+              \n
+    ");
+}
+
+#[test]
+fn test_insert_mode_drops_characters_past_the_margin() {
+    let mut tty = VirtualTty::new(6, 2);
+    write!(tty.stdout, "abcdef\x1b[3G\x1b[4hXY").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    abXYcd\n
+          \n
+    ");
+}
+
+#[test]
+fn test_replace_mode_is_default_and_can_be_restored() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "abc\x1b[1G\x1b[4hX\x1b[4lY").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    XYbc      \n
+              \n
+    ");
+}
+
+#[test]
+fn test_insert_mode_with_wide_character() {
+    let mut tty = VirtualTty::new(8, 1);
+    write!(tty.stdout, "abc\x1b[1G\x1b[4h日").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @"日abc   \\n");
+}
+
+// =============================================================================
+// LNM (CSI 20 h / CSI 20 l)
+// =============================================================================
+
+#[test]
+fn test_line_feed_keeps_column_without_newline_mode() {
+    let mut tty = VirtualTty::new(10, 3);
+    write!(tty.stdout, "abc\ndef\x0bg").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    abc       \n
+       def    \n
+          g   \n
+    ");
+}
+
+#[test]
+fn test_newline_mode_returns_to_first_column() {
+    let mut tty = VirtualTty::new(10, 3);
+    write!(tty.stdout, "\x1b[20habc\ndef\x0bg").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    abc       \n
+    def       \n
+    g         \n
+    ");
+
+    write!(tty.stdout, "\x1b[20l\nh").unwrap();
+    assert_eq!(tty.get_cursor_position(), (2, 2));
+}
+
+#[test]
+fn test_newline_mode_is_reset() {
+    let mut tty = VirtualTty::new(10, 3);
+    write!(tty.stdout, "\x1b[20h\x1bcabc\nd").unwrap();
+    assert_eq!(tty.get_cursor_position(), (1, 4));
+}
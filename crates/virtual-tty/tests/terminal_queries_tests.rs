@@ -0,0 +1,99 @@
+use std::io::Write;
+use virtual_tty::{DeviceIdentity, VirtualTty};
+
+// =============================================================================
+// DSR / CPR (CSI 5 n / CSI 6 n)
+// =============================================================================
+
+#[test]
+fn test_status_report() {
+    let mut tty = VirtualTty::new(10, 3);
+    write!(tty.stdout, "\x1b[5n").unwrap();
+    assert_eq!(tty.take_responses(), vec!["\x1b[0n".to_string()]);
+}
+
+#[test]
+fn test_cursor_position_report() {
+    let mut tty = VirtualTty::new(10, 3);
+    write!(tty.stdout, "abc\r\nde\x1b[6n").unwrap();
+    assert_eq!(tty.take_responses(), vec!["\x1b[2;3R".to_string()]);
+    // Responses are only drained once
+    assert!(tty.take_responses().is_empty());
+}
+
+#[test]
+fn test_cursor_position_report_in_origin_mode() {
+    let mut tty = VirtualTty::new(10, 6);
+    write!(
+        tty.stdout,
+        "\x1b[?69h\x1b[3;5r\x1b[4;8s\x1b[?6h\x1b[2;3H\x1b[6n"
+    )
+    .unwrap();
+    // The cursor is at row 4, column 6 of the screen
+    assert_eq!(tty.get_cursor_position(), (3, 5));
+    assert_eq!(tty.take_responses(), vec!["\x1b[2;3R".to_string()]);
+}
+
+#[test]
+fn test_query_is_not_printed() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "a\x1b[6nb").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @"ab        \\n");
+}
+
+// =============================================================================
+// DEVICE ATTRIBUTES AND VERSION (CSI c / CSI > c / CSI > q)
+// =============================================================================
+
+#[test]
+fn test_default_device_identity() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b[c\x1b[>c\x1b[>q").unwrap();
+    assert_eq!(
+        tty.take_responses(),
+        vec![
+            "\x1b[?62;22c".to_string(),
+            "\x1b[>1;0;0c".to_string(),
+            format!("\x1bP>|virtual-tty({})\x1b\\", env!("CARGO_PKG_VERSION")),
+        ]
+    );
+}
+
+#[test]
+fn test_configured_device_identity() {
+    let mut tty = VirtualTty::new(10, 1);
+    tty.set_device_identity(DeviceIdentity {
+        primary_attributes: vec![64, 1, 2, 22],
+        terminal_type: 41,
+        firmware_version: 388,
+        name: "xterm(388)".to_string(),
+    });
+    write!(tty.stdout, "\x1b[0c\x1b[>0c\x1b[>0q").unwrap();
+    assert_eq!(
+        tty.take_responses(),
+        vec![
+            "\x1b[?64;1;2;22c".to_string(),
+            "\x1b[>41;388;0c".to_string(),
+            "\x1bP>|xterm(388)\x1b\\".to_string(),
+        ]
+    );
+    // The identity belongs to the terminal and survives a reset
+    write!(tty.stdout, "\x1bc\x1b[>c").unwrap();
+    assert_eq!(tty.take_responses(), vec!["\x1b[>41;388;0c".to_string()]);
+}
+
+#[test]
+fn test_responses_are_queued_in_order() {
+    let mut tty = VirtualTty::new(10, 2);
+    tty.set_clipboard("x");
+    write!(tty.stdout, "\x1b[6n\x1b]52;c;?\x07\x1b[5n").unwrap();
+    assert_eq!(
+        tty.take_responses(),
+        vec![
+            "\x1b[1;1R".to_string(),
+            "\x1b]52;c;eA==\x1b\\".to_string(),
+            "\x1b[0n".to_string(),
+        ]
+    );
+}
@@ -0,0 +1,120 @@
+use std::io::Write;
+use virtual_tty::{SavedTitle, VirtualTty};
+
+// =============================================================================
+// OSC 0/1/2
+// =============================================================================
+
+#[test]
+fn test_osc_title_is_not_printed() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "\x1b]0;vim main.rs\x07Hello").unwrap();
+    assert_eq!(tty.get_title(), "vim main.rs");
+    assert_eq!(tty.get_icon_name(), "vim main.rs");
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    Hello     \n
+              \n
+    ");
+}
+
+#[test]
+fn test_osc_terminated_by_string_terminator() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b]2;build: ok\x1b\\done").unwrap();
+    assert_eq!(tty.get_title(), "build: ok");
+    assert_eq!(tty.get_icon_name(), "");
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @"done      \\n");
+}
+
+#[test]
+fn test_window_title_and_icon_name_are_separate() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b]0;both\x07\x1b]1;icon\x07").unwrap();
+    assert_eq!(tty.get_title(), "both");
+    assert_eq!(tty.get_icon_name(), "icon");
+    write!(tty.stdout, "\x1b]2;window\x07").unwrap();
+    assert_eq!(tty.get_title(), "window");
+    assert_eq!(tty.get_icon_name(), "icon");
+}
+
+#[test]
+fn test_title_split_across_writes() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "a\x1b]0;long ti").unwrap();
+    write!(tty.stdout, "tle\x1b").unwrap();
+    write!(tty.stdout, "\\b").unwrap();
+    assert_eq!(tty.get_title(), "long title");
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @"ab        \\n");
+}
+
+#[test]
+fn test_unknown_osc_is_ignored() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b]4242;data\x07ok").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @"ok        \\n");
+}
+
+#[test]
+fn test_title_survives_reset() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b]0;shell\x07\x1bc").unwrap();
+    assert_eq!(tty.get_title(), "shell");
+}
+
+// =============================================================================
+// TITLE STACK (CSI 22 t / CSI 23 t)
+// =============================================================================
+
+#[test]
+fn test_push_and_pop_title() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(tty.stdout, "\x1b]0;shell\x07\x1b[22;0t\x1b]0;vim\x07").unwrap();
+    assert_eq!(tty.get_title(), "vim");
+    assert_eq!(
+        tty.get_title_stack(),
+        vec![SavedTitle {
+            title: Some("shell".to_string()),
+            icon_name: Some("shell".to_string()),
+        }]
+    );
+
+    write!(tty.stdout, "\x1b[23;0t").unwrap();
+    assert_eq!(tty.get_title(), "shell");
+    assert_eq!(tty.get_icon_name(), "shell");
+    assert!(tty.get_title_stack().is_empty());
+}
+
+#[test]
+fn test_push_window_title_only() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(
+        tty.stdout,
+        "\x1b]0;shell\x07\x1b[22;2t\x1b]0;vim\x07\x1b[23;0t"
+    )
+    .unwrap();
+    // Only the window title was saved, the icon name stays
+    assert_eq!(tty.get_title(), "shell");
+    assert_eq!(tty.get_icon_name(), "vim");
+}
+
+#[test]
+fn test_nested_title_stack() {
+    let mut tty = VirtualTty::new(10, 1);
+    write!(
+        tty.stdout,
+        "\x1b]2;a\x07\x1b[22t\x1b]2;b\x07\x1b[22t\x1b]2;c\x07"
+    )
+    .unwrap();
+    assert_eq!(tty.get_title_stack().len(), 2);
+    write!(tty.stdout, "\x1b[23t").unwrap();
+    assert_eq!(tty.get_title(), "b");
+    write!(tty.stdout, "\x1b[23t").unwrap();
+    assert_eq!(tty.get_title(), "a");
+    // Popping an empty stack keeps the title
+    write!(tty.stdout, "\x1b[23t").unwrap();
+    assert_eq!(tty.get_title(), "a");
+}
@@ -0,0 +1,60 @@
+use std::io::Write;
+use virtual_tty::VirtualTty;
+
+// =============================================================================
+// MULTIBYTE CHARACTERS SPLIT ACROSS WRITES
+// =============================================================================
+
+#[test]
+fn test_character_split_across_writes() {
+    let mut tty = VirtualTty::new(10, 1);
+    let bytes = "é日🚀".as_bytes();
+    for byte in bytes {
+        tty.stdout.write_all(&[*byte]).unwrap();
+    }
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @"é日🚀     \\n");
+}
+
+#[test]
+fn test_character_split_between_streams() {
+    let mut tty = VirtualTty::new(10, 1);
+    let bytes = "日".as_bytes();
+    tty.stdout.write_all(&bytes[..1]).unwrap();
+    tty.stderr.write_all(&bytes[1..]).unwrap();
+    assert_eq!(tty.cell_at(0, 0).unwrap().ch, '日');
+    assert_eq!(tty.get_cursor_position(), (0, 2));
+}
+
+#[test]
+fn test_character_split_after_escape_sequence() {
+    let mut tty = VirtualTty::new(10, 1);
+    let bytes = "x\x1b[3G本".as_bytes();
+    let (first, second) = bytes.split_at(bytes.len() - 1);
+    tty.stdout.write_all(first).unwrap();
+    tty.stdout.write_all(second).unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @"x 本      \\n");
+}
+
+// =============================================================================
+// INVALID INPUT
+// =============================================================================
+
+#[test]
+fn test_invalid_bytes_become_replacement_characters() {
+    let mut tty = VirtualTty::new(10, 1);
+    tty.stdout.write_all(b"a\xffb\xc3(").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @"a�b�(     \\n");
+}
+
+#[test]
+fn test_interrupted_character_becomes_replacement_character() {
+    let mut tty = VirtualTty::new(10, 1);
+    tty.stdout.write_all(b"\xe6\x97").unwrap();
+    assert_eq!(tty.get_cursor_position(), (0, 0));
+    tty.stdout.write_all(b"x").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @"�x        \\n");
+}
@@ -0,0 +1,164 @@
+use std::io::Write;
+use virtual_tty::VirtualTty;
+
+// =============================================================================
+// DOUBLE-WIDTH CHARACTERS
+// =============================================================================
+
+#[test]
+fn test_cjk_characters_take_two_cells() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "日本語!").unwrap();
+    assert_eq!(tty.get_cursor_position(), (0, 7));
+
+    let first = tty.cell_at(0, 0).unwrap();
+    assert_eq!(first.ch, '日');
+    assert_eq!(first.width, 2);
+    assert!(tty.cell_at(0, 1).unwrap().is_continuation());
+    assert_eq!(tty.cell_at(0, 6).unwrap().ch, '!');
+
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    日本語!   \n
+              \n
+    ");
+}
+
+#[test]
+fn test_emoji_take_two_cells() {
+    let mut tty = VirtualTty::new(10, 2);
+    write!(tty.stdout, "ok 🚀 done").unwrap();
+    assert_eq!(tty.cell_at(0, 3).unwrap().ch, '🚀');
+    assert_eq!(tty.cell_at(0, 6).unwrap().ch, 'd');
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    ok 🚀 done\n
+              \n
+    ");
+}
+
+#[test]
+fn test_fullwidth_forms() {
+    let mut tty = VirtualTty::new(6, 1);
+    write!(tty.stdout, "ＡＢ").unwrap();
+    assert_eq!(tty.get_cursor_position(), (0, 4));
+}
+
+#[test]
+fn test_wide_character_wraps_when_it_does_not_fit() {
+    let mut tty = VirtualTty::new(5, 3);
+    write!(tty.stdout, "abcd日x").unwrap();
+    // The last column stays blank and the character moves to the next line
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    abcd \n
+    日x  \n
+         \n
+    ");
+}
+
+#[test]
+fn test_wide_character_filling_the_line_wraps_the_cursor() {
+    let mut tty = VirtualTty::new(4, 3);
+    write!(tty.stdout, "ab日c").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    ab日\n
+    c   \n
+        \n
+    ");
+}
+
+// =============================================================================
+// OVERWRITING WIDE CHARACTERS
+// =============================================================================
+
+#[test]
+fn test_overwriting_left_half_erases_wide_character() {
+    let mut tty = VirtualTty::new(6, 1);
+    write!(tty.stdout, "日本\x1b[1Gx").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    x 本  \n
+    ");
+}
+
+#[test]
+fn test_overwriting_right_half_erases_wide_character() {
+    let mut tty = VirtualTty::new(6, 1);
+    write!(tty.stdout, "日本\x1b[4Gx").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    日 x  \n
+    ");
+}
+
+#[test]
+fn test_wide_characters_in_styled_snapshot() {
+    let mut tty = VirtualTty::new(8, 1);
+    write!(tty.stdout, "\x1b[31m日本\x1b[0m ok").unwrap();
+    let snapshot = tty.get_styled_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    [fg=red]日本[/] ok \n
+    ");
+}
+
+// =============================================================================
+// EDITING AROUND WIDE CHARACTERS
+// =============================================================================
+
+#[test]
+fn test_erase_chars_on_either_half_of_wide_character() {
+    let mut tty = VirtualTty::new(8, 2);
+    // On the first half of 本, then on the second half of 日
+    write!(tty.stdout, "a日本x\x1b[1;4H\x1b[X").unwrap();
+    write!(tty.stdout, "\x1b[2;1Ha日本x\x1b[2;3H\x1b[X").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    a日  x  \n
+    a  本x  \n
+    ");
+}
+
+#[test]
+fn test_delete_chars_on_either_half_of_wide_character() {
+    let mut tty = VirtualTty::new(8, 2);
+    write!(tty.stdout, "a日本x\x1b[1;4H\x1b[P").unwrap();
+    write!(tty.stdout, "\x1b[2;1Ha日本x\x1b[2;3H\x1b[P").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    a日 x   \n
+    a 本x   \n
+    ");
+}
+
+#[test]
+fn test_insert_chars_on_either_half_of_wide_character() {
+    let mut tty = VirtualTty::new(8, 3);
+    write!(tty.stdout, "a日本x\x1b[1;4H\x1b[@").unwrap();
+    write!(tty.stdout, "\x1b[2;1Ha日本x\x1b[2;3H\x1b[@").unwrap();
+    // A wide character pushed onto the last column loses its second half
+    write!(tty.stdout, "\x1b[3;1Habcde日\x1b[3;1H\x1b[2@").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    a日 本x \n
+    a   本x \n
+      abcde \n
+    ");
+}
+
+#[test]
+fn test_erase_line_on_either_half_of_wide_character() {
+    let mut tty = VirtualTty::new(8, 4);
+    write!(tty.stdout, "a日本x\x1b[1;4H\x1b[K").unwrap();
+    write!(tty.stdout, "\x1b[2;1Ha日本x\x1b[2;3H\x1b[K").unwrap();
+    write!(tty.stdout, "\x1b[3;1Ha日本x\x1b[3;2H\x1b[1K").unwrap();
+    write!(tty.stdout, "\x1b[4;1Ha日本x\x1b[4;5H\x1b[1K").unwrap();
+    let snapshot = tty.get_snapshot();
+    insta::assert_snapshot!(snapshot, @r"
+    a日     \n
+    a       \n
+       本x  \n
+         x  \n
+    ");
+}
@@ -45,6 +45,10 @@ class VirtualTtyStreams {
   getSnapshot(): string {
     return this.wasm.getSnapshot();
   }
+
+  getStyledSnapshot(): string {
+    return this.wasm.getStyledSnapshot();
+  }
 }
 
 /**